@@ -0,0 +1,182 @@
+title = Schere Stein Papier Echse Spock
+error = Ein Fehler ist aufgetreten
+game-not-found = Spiel nicht gefunden
+game-running = Dieses Spiel läuft noch
+joined-game = Du bist Spiel { $id } beigetreten
+component-outdated = Dieser Button ist veraltet, bitte starte ein neues Spiel
+component-invalid = Dieser Button gehört nicht zu diesem Spiel
+game-error-already-joined = Du bist diesem Spiel schon beigetreten
+game-error-already-started = Dieses Spiel hat schon begonnen
+game-error-not-enough-players = Zum Starten werden mindestens zwei Spieler gebraucht
+game-error-not-started = Dieses Spiel hat noch nicht begonnen
+game-error-not-a-player = Du spielst in diesem Spiel nicht mit
+game-error-choice-not-allowed = Diese Waffe gehört nicht zu diesem Spiel
+game-error-not-finished = Es wurden noch nicht alle Runden dieses Spiels gespielt
+game-error-game-over = Dieses Spiel ist vorbei
+no-stored-games = Für diesen Server sind keine beendeten Spiele gespeichert
+
+## Game embed
+
+game-footer = Spiel { $id }
+lobby-rounds = Runden:{ $rounds }
+lobby-players = Spieler:
+lobby-code = Tritt von überall mit `/ssp beitreten code:{ $code }` bei
+field-players = Spieler
+field-choices = Auswahl
+field-results = Ergebnisse
+field-points = Punkte
+round-counter = Runde { $round }/{ $rounds }
+choose-weapon = Wähle deine Waffe
+players-chose = { $chosen }/{ $players } Spieler haben gewählt
+all-chose = Alle Spieler haben { $choice } gewählt
+no-one-wins = Niemand gewinnt
+cyclic-draw = Jede Waffe wurde gewählt, also liegt niemand vorne
+round-replayed = Die Runde wird wiederholt
+points = { $points ->
+    [one] { $points } Punkt
+   *[other] { $points } Punkte
+}
+round-points = +{ $points }
+no-points = Keine Punkte vergeben
+and-more = … und { $count } weitere
+round-deadline = Die Runde endet { $deadline }
+channel-not-allowed = Spiele sind nur in { $channels } erlaubt
+role-not-allowed = Du brauchst eine dieser Rollen, um zu spielen: { $roles }
+mode-not-allowed = Dieser Modus ist auf diesem Server nicht erlaubt
+rounds-capped = Auf diesem Server sind höchstens { $max } Runden erlaubt, daher hat das Spiel { $max } Runden.
+game-force-ended = Dieses Spiel wurde von einem Administrator beendet.
+game-unreachable = Dieses Spiel wurde beendet, weil seine Nachricht nicht aktualisiert werden konnte.
+bot-restarting = Der Bot startet neu, dieses Spiel wurde beendet. Bitte starte gleich ein neues.
+
+## History
+
+history-footer = Spiel { $id } - Seite { $page }/{ $pages }
+history-round = Verlauf - Runde { $round }/{ $rounds }
+history-empty = Es wurden noch keine Runden gespielt
+
+## Full results
+
+standings-footer = Spiel { $id } - Seite { $page }/{ $pages }
+standings-own-rank = Du bist auf Platz { $rank } von { $players } mit { $points ->
+    [one] { $points } Punkt
+   *[other] { $points } Punkten
+}
+
+## Buttons
+
+button-join = Beitreten
+button-leave = Verlassen
+button-start = Starten
+button-history = Verlauf
+button-full-results = Alle Ergebnisse
+button-previous = Zurück
+button-next = Weiter
+
+## Rules
+
+choice-rock = Stein
+choice-paper = Papier
+choice-scissors = Schere
+choice-lizard = Echse
+choice-spock = Spock
+verb-covers = bedeckt
+verb-crushes = zerquetscht
+verb-decapitates = köpft
+verb-poisons = vergiftet
+verb-vaporizes = verdampft
+verb-eats = frisst
+verb-disproves = widerlegt
+verb-smashes = zertrümmert
+verb-cuts = schneidet
+rule = { $winner } { $verb } { $loser }
+rule-set-classic = Schere Stein Papier
+rule-set-lizard-spock = Schere Stein Papier Echse Spock
+
+## Analysis
+
+analysis-of = Analyse von { $user } - { $picks } Züge
+analysis-no-picks = Noch keine Züge aufgezeichnet
+analysis-not-enough = Zu wenige Runden gespielt
+analysis-transition = nach { $from } folgt { $to } { $share }% ({ $count }/{ $total })
+analysis-stay = bleiben
+analysis-switch = wechseln
+field-transitions = Übergänge
+field-after-winning = Nach einem Sieg
+field-after-losing = Nach einer Niederlage
+field-predictability = Vorhersagbarkeit
+
+## Settings
+
+config-title = Einstellungen
+config-key-default-rounds = Standardrunden
+config-key-max-rounds = Maximale Runden
+config-key-round-timeout = Rundenzeit
+config-key-allowed-modes = Erlaubte Modi
+config-key-default-rule-set = Standardregeln
+config-key-allowed-channels = Erlaubte Kanäle
+config-key-allowed-roles = Erlaubte Rollen
+config-key-language = Sprache
+config-key-cyclic-policy = Wenn jede Waffe gewählt wird
+config-off = aus
+config-auto = Serversprache
+cyclic-policy-replay = Runde wiederholen
+cyclic-policy-no-points = keine Punkte
+cyclic-policy-pairwise = jedes Paar werten
+config-all-channels = alle Kanäle
+config-all-roles = alle
+config-saved = Einstellung gespeichert
+config-reset-done = Einstellungen zurückgesetzt
+config-admin-only = Nur Mitglieder, die den Server verwalten dürfen, können die Einstellungen ändern
+config-unknown-key = Unbekannte Einstellung
+config-invalid-rounds = Die Rundenzahl muss zwischen 1 und { $max } liegen
+config-invalid-timeout = Die Rundenzeit muss 0 oder eine Sekundenzahl zwischen { $min } und { $max } sein
+config-invalid-rule-set = Unbekannte Regeln, erlaubt sind: { $rule_sets }
+config-no-modes = Mindestens ein Modus muss erlaubt sein
+config-default-not-allowed = Die Standardregeln müssen zu den erlaubten Modi gehören
+config-invalid-channel = { $channel } ist kein Kanal
+config-invalid-role = { $role } ist keine Rolle
+config-invalid-language = Unbekannte Sprache, erlaubt sind en, de oder auto
+config-invalid-cyclic-policy = Unbekannte Regel, erlaubt sind: { $policies }
+
+## Slash commands
+
+command-rockpaperscissors-name = scheresteinpapier
+command-rockpaperscissors-description = Starte ein neues Spiel
+command-rules-name = regeln
+command-rules-description = Zeige die Spielregeln.
+command-rps-name = ssp
+command-rps-description = Schere Stein Papier Echse Spock
+command-history-name = verlauf
+command-history-description = Zeige jede Runde eines Spiels
+command-analyze-name = analyse
+command-analyze-description = Zeige, wie ein Spieler seine Waffen wählt
+command-export-name = export
+command-export-description = Lade ein beendetes Spiel herunter
+command-export-all-name = export-alle
+command-export-all-description = Lade alle beendeten Spiele dieses Servers herunter
+command-join-name = beitreten
+command-join-description = Tritt einem Spiel mit seinem Code bei
+option-rounds-name = runden
+option-rounds-description = wie viele Runden gespielt werden
+option-game-id-name = spiel_id
+option-game-id-description = Die Id in der Fußzeile des Spiels
+option-user-name = spieler
+option-user-description = Der zu analysierende Spieler, standardmäßig du selbst
+option-format-name = format
+option-format-description = Das Dateiformat
+option-code-name = code
+option-code-description = Der Code, der im Spiel angezeigt wird
+command-rps-config-name = ssp-einstellungen
+command-rps-config-description = Ändere die Spieleinstellungen dieses Servers
+command-config-get-name = anzeigen
+command-config-get-description = Zeige die aktuellen Einstellungen
+command-config-set-name = setzen
+command-config-set-description = Ändere eine Einstellung
+command-config-reset-name = zuruecksetzen
+command-config-reset-description = Setze eine oder alle Einstellungen zurück
+option-mode-name = modus
+option-mode-description = Die Regeln, mit denen gespielt wird
+option-key-name = einstellung
+option-key-description = Die Einstellung
+option-value-name = wert
+option-value-description = Der neue Wert
@@ -0,0 +1,182 @@
+title = Rock Paper Scissors Lizard Spock
+error = An error occurred
+game-not-found = Game not found
+game-running = This game is still running
+joined-game = You joined game { $id }
+component-outdated = This button is outdated, please start a new game
+component-invalid = This button does not belong to this game
+game-error-already-joined = You already joined this game
+game-error-already-started = This game has already started
+game-error-not-enough-players = At least two players are needed to start
+game-error-not-started = This game has not started yet
+game-error-not-a-player = You are not playing in this game
+game-error-choice-not-allowed = This weapon is not part of this game
+game-error-not-finished = Not every round of this game was played
+game-error-game-over = This game is over
+no-stored-games = No finished games are stored for this server
+
+## Game embed
+
+game-footer = Game { $id }
+lobby-rounds = Rounds:{ $rounds }
+lobby-players = Players:
+lobby-code = Join from anywhere with `/rps join code:{ $code }`
+field-players = Players
+field-choices = Choices
+field-results = Results
+field-points = Points
+round-counter = round { $round }/{ $rounds }
+choose-weapon = Choose your weapon
+players-chose = { $chosen }/{ $players } players chose
+all-chose = All players chose { $choice }
+no-one-wins = No one wins
+cyclic-draw = Every weapon was chosen, so no one is ahead
+round-replayed = The round is played again
+points = { $points ->
+    [one] { $points } point
+   *[other] { $points } points
+}
+round-points = +{ $points }
+no-points = No points awarded
+and-more = … and { $count } more
+round-deadline = Round ends { $deadline }
+channel-not-allowed = Games can only be played in { $channels }
+role-not-allowed = You need one of these roles to play: { $roles }
+mode-not-allowed = This mode is not allowed on this server
+rounds-capped = This server allows at most { $max } rounds, so the game has { $max } rounds.
+game-force-ended = This game was ended by an administrator.
+game-unreachable = This game ended because its message could not be updated.
+bot-restarting = The bot is restarting, this game has been ended. Please start a new one in a moment.
+
+## History
+
+history-footer = Game { $id } - page { $page }/{ $pages }
+history-round = History - round { $round }/{ $rounds }
+history-empty = No rounds have been played yet
+
+## Full results
+
+standings-footer = Game { $id } - page { $page }/{ $pages }
+standings-own-rank = You are ranked #{ $rank } of { $players } with { $points ->
+    [one] { $points } point
+   *[other] { $points } points
+}
+
+## Buttons
+
+button-join = Join
+button-leave = Leave
+button-start = Start
+button-history = History
+button-full-results = Full results
+button-previous = Previous
+button-next = Next
+
+## Rules
+
+choice-rock = Rock
+choice-paper = Paper
+choice-scissors = Scissors
+choice-lizard = Lizard
+choice-spock = Spock
+verb-covers = covers
+verb-crushes = crushes
+verb-decapitates = decapitates
+verb-poisons = poisons
+verb-vaporizes = vaporizes
+verb-eats = eats
+verb-disproves = disproves
+verb-smashes = smashes
+verb-cuts = cuts
+rule = { $winner } { $verb } { $loser }
+rule-set-classic = Rock Paper Scissors
+rule-set-lizard-spock = Rock Paper Scissors Lizard Spock
+
+## Analysis
+
+analysis-of = Analysis of { $user } - { $picks } picks
+analysis-no-picks = No picks recorded yet
+analysis-not-enough = Not enough rounds played
+analysis-transition = after { $from } they pick { $to } { $share }% ({ $count }/{ $total })
+analysis-stay = stay
+analysis-switch = switch
+field-transitions = Transitions
+field-after-winning = After winning
+field-after-losing = After losing
+field-predictability = Predictability
+
+## Settings
+
+config-title = Settings
+config-key-default-rounds = Default rounds
+config-key-max-rounds = Maximum rounds
+config-key-round-timeout = Round timeout
+config-key-allowed-modes = Allowed modes
+config-key-default-rule-set = Default rule set
+config-key-allowed-channels = Allowed channels
+config-key-allowed-roles = Allowed roles
+config-key-language = Language
+config-key-cyclic-policy = When every weapon is chosen
+config-off = off
+config-auto = server language
+cyclic-policy-replay = replay the round
+cyclic-policy-no-points = no points
+cyclic-policy-pairwise = score every pair
+config-all-channels = all channels
+config-all-roles = everyone
+config-saved = Setting saved
+config-reset-done = Settings reset
+config-admin-only = Only members who can manage the server can change the settings
+config-unknown-key = Unknown setting
+config-invalid-rounds = Rounds must be a number from 1 to { $max }
+config-invalid-timeout = The timeout must be 0 or a number of seconds from { $min } to { $max }
+config-invalid-rule-set = Unknown rule set, use one of: { $rule_sets }
+config-no-modes = At least one mode must be allowed
+config-default-not-allowed = The default rule set must be one of the allowed modes
+config-invalid-channel = { $channel } is not a channel
+config-invalid-role = { $role } is not a role
+config-invalid-language = Unknown language, use en, de or auto
+config-invalid-cyclic-policy = Unknown policy, use one of: { $policies }
+
+## Slash commands
+
+command-rockpaperscissors-name = rockpaperscissors
+command-rockpaperscissors-description = Start a new Game
+command-rules-name = rules
+command-rules-description = Get the rules of the game.
+command-rps-name = rps
+command-rps-description = Rock Paper Scissors Lizard Spock
+command-history-name = history
+command-history-description = Replay every round of a game
+command-analyze-name = analyze
+command-analyze-description = Show how a player picks their weapons
+command-export-name = export
+command-export-description = Download a finished game
+command-export-all-name = export-all
+command-export-all-description = Download every finished game of this server
+command-join-name = join
+command-join-description = Join a game by its code
+option-rounds-name = rounds
+option-rounds-description = how many rounds are played
+option-game-id-name = game_id
+option-game-id-description = The id shown in the footer of the game
+option-user-name = user
+option-user-description = The player to analyze, yourself by default
+option-format-name = format
+option-format-description = The file format
+option-code-name = code
+option-code-description = The code shown in the game
+command-rps-config-name = rps-config
+command-rps-config-description = Change the game settings of this server
+command-config-get-name = get
+command-config-get-description = Show the current settings
+command-config-set-name = set
+command-config-set-description = Change a setting
+command-config-reset-name = reset
+command-config-reset-description = Reset one or all settings to the defaults
+option-mode-name = mode
+option-mode-description = The rule set to play with
+option-key-name = key
+option-key-description = The setting
+option-value-name = value
+option-value-description = The new value
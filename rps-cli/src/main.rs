@@ -0,0 +1,239 @@
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rps_core::choice::Choice;
+use rps_core::game::Game;
+use rps_core::outcome::{CyclicPolicy, RoundOutcome};
+use rps_core::ruleset::RuleSet;
+use rps_core::simulation::{default_strategies, Strategy};
+use std::io::{self, BufRead, Write};
+use std::process::exit;
+
+const USAGE: &str =
+    "usage: rps-cli [--rounds N] [--bot STRATEGY] [--seed N] [--cyclic POLICY] [PLAYER...]";
+
+struct Options {
+    rounds: u64,
+    bot: Option<Box<dyn Strategy>>,
+    seed: Option<u64>,
+    cyclic_policy: CyclicPolicy,
+    players: Vec<String>,
+}
+
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options {
+        rounds: 3,
+        bot: None,
+        seed: None,
+        cyclic_policy: CyclicPolicy::default(),
+        players: vec![],
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rounds" => {
+                options.rounds = args
+                    .next()
+                    .and_then(|rounds| rounds.parse().ok())
+                    .filter(|rounds| *rounds > 0)
+                    .ok_or("--rounds needs a positive number")?
+            }
+            "--seed" => {
+                options.seed = Some(
+                    args.next()
+                        .and_then(|seed| seed.parse().ok())
+                        .ok_or("--seed needs a number")?,
+                )
+            }
+            "--cyclic" => {
+                let name = args.next().ok_or("--cyclic needs a policy")?;
+                options.cyclic_policy = CyclicPolicy::from_name(&name).ok_or_else(|| {
+                    format!(
+                        "unknown policy {}, available: {}",
+                        name,
+                        CyclicPolicy::all()
+                            .into_iter()
+                            .map(|policy| policy.name())
+                            .join(", ")
+                    )
+                })?;
+            }
+            "--bot" => {
+                let name = args.next().ok_or("--bot needs a strategy")?;
+                let strategy = default_strategies()
+                    .into_iter()
+                    .map(|factory| factory())
+                    .find(|strategy| strategy.name() == name)
+                    .ok_or_else(|| {
+                        format!(
+                            "unknown strategy {}, available: {}",
+                            name,
+                            default_strategies()
+                                .into_iter()
+                                .map(|factory| factory().name())
+                                .join(", ")
+                        )
+                    })?;
+                options.bot = Some(strategy);
+            }
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => options.players.push(arg),
+        }
+    }
+    if options.players.is_empty() && options.bot.is_some() {
+        options.players.push("player".to_string());
+    }
+    if options.players.len() + options.bot.iter().count() < 2 {
+        return Err(format!("at least two players are needed\n{}", USAGE));
+    }
+    if !options.players.iter().all_unique() {
+        return Err("player names must be unique".to_string());
+    }
+    Ok(options)
+}
+
+fn read_choice(input: &mut impl BufRead, player: &str) -> io::Result<Choice> {
+    loop {
+        print!(
+            "{}, choose {}: ",
+            player,
+            Choice::all()
+                .into_iter()
+                .map(|choice| format!("{} {}", choice.emoji(), choice.name()))
+                .join(" / ")
+        );
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "input closed"));
+        }
+        let line = line.trim();
+        let choice = match line.chars().exactly_one() {
+            Ok(c) => Choice::from_char(c),
+            Err(_) => Choice::from_name(line),
+        };
+        match choice {
+            Some(choice) => return Ok(choice),
+            None => println!("Unknown weapon {:?}", line),
+        }
+    }
+}
+
+fn print_points(game: &Game<String>) {
+    game.get_points()
+        .iter()
+        .sorted_by_key(|(player, points)| (std::cmp::Reverse(**points), *player))
+        .for_each(|(player, points)| println!("  {} {} points", player, points));
+}
+
+fn main() {
+    let mut options = match parse_options() {
+        Ok(options) => options,
+        Err(why) => {
+            eprintln!("{}", why);
+            exit(2);
+        }
+    };
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let bot_name = options
+        .bot
+        .as_ref()
+        .map(|bot| format!("bot ({})", bot.name()));
+    let mut game = Game::new("cli".to_string(), RuleSet::LizardSpock, options.rounds);
+    game.set_cyclic_policy(options.cyclic_policy);
+    options
+        .players
+        .iter()
+        .chain(bot_name.iter())
+        .for_each(|player| {
+            if let Err(why) = game.add_player(player) {
+                eprintln!("{}: {}", player, why);
+                exit(2);
+            }
+        });
+    if let Err(why) = game.start() {
+        eprintln!("{}", why);
+        exit(2);
+    }
+    let hot_seat = options.players.len() > 1;
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut bot_choices = vec![];
+    let mut opponent_choices = vec![];
+    while !game.is_done() {
+        println!("\nRound {}/{}", game.get_round(), game.get_rounds());
+        for player in &options.players {
+            let choice = match read_choice(&mut input, player) {
+                Ok(choice) => choice,
+                Err(why) => {
+                    eprintln!("{}", why);
+                    exit(1);
+                }
+            };
+            if let Err(why) = game.choose(player, choice) {
+                eprintln!("{}", why);
+                exit(1);
+            }
+            if hot_seat {
+                print!("\x1B[2J\x1B[1;1H");
+            }
+        }
+        if let (Some(bot), Some(bot_name)) = (options.bot.as_mut(), bot_name.as_ref()) {
+            let choice = bot.choose(&mut rng, &bot_choices, &opponent_choices);
+            if let Err(why) = game.choose(bot_name, choice) {
+                eprintln!("{}", why);
+                exit(1);
+            }
+            bot_choices.push(choice);
+        }
+        if let Err(why) = game.resolve_round() {
+            eprintln!("{}", why);
+            exit(1);
+        }
+        let round = match game.get_history().last() {
+            Some(round) => round,
+            None => continue,
+        };
+        if let Some(choice) = options
+            .players
+            .first()
+            .and_then(|player| round.choices.get(player))
+        {
+            opponent_choices.push(*choice);
+        }
+        round
+            .choices
+            .iter()
+            .sorted_by_key(|(player, _)| *player)
+            .for_each(|(player, choice)| println!("  {} chose {}", player, choice.emoji()));
+        match round.outcome {
+            RoundOutcome::Unanimous(choice) => {
+                println!("All players chose {}\nNo one wins", choice.emoji())
+            }
+            RoundOutcome::CyclicDraw if options.cyclic_policy == CyclicPolicy::Replay => {
+                println!("Every weapon was chosen, the round is played again")
+            }
+            RoundOutcome::CyclicDraw if round.results.is_empty() => {
+                println!("Every weapon was chosen\nNo one wins")
+            }
+            _ if round.results.is_empty() => println!("No one wins"),
+            _ => round.results.iter().for_each(|result| {
+                println!(
+                    "{} × {} {} {} × {}",
+                    result.winners,
+                    result.winner_choice.emoji(),
+                    result.verb,
+                    result.losers,
+                    result.loser_choice.emoji()
+                )
+            }),
+        }
+        println!("Points:");
+        print_points(&game);
+    }
+    println!("\nFinal standings:");
+    print_points(&game);
+}
@@ -0,0 +1,40 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rps_core::choice::Choice;
+use rps_core::game::Game;
+use rps_core::ruleset::RuleSet;
+
+fn game_with_choices(players: u64) -> Game<u64> {
+    let mut rng = StdRng::seed_from_u64(players);
+    let mut game = Game::new("bench".to_string(), RuleSet::LizardSpock, 1);
+    (0..players).for_each(|player| game.add_player(&player).unwrap());
+    game.start().unwrap();
+    (0..players).for_each(|player| {
+        game.choose(
+            &player,
+            *Choice::all().choose(&mut rng).unwrap_or(&Choice::Rock),
+        )
+        .unwrap()
+    });
+    game
+}
+
+fn resolve_round(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resolve_round");
+    for players in [10, 200, 1000, 5000] {
+        let game = game_with_choices(players);
+        group.bench_with_input(BenchmarkId::from_parameter(players), &game, |b, game| {
+            b.iter_batched_ref(
+                || game.clone(),
+                |game| game.resolve_round(),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, resolve_round);
+criterion_main!(benches);
@@ -1,15 +1,14 @@
-use crate::{battleresult::BattleResult, choice::Choice};
-use serenity::model::user::User;
+use crate::{battleresult::BattleResult, choice::Choice, player::Player};
 
-pub struct Battle {
-    player1: User,
+pub struct Battle<P: Player> {
+    player1: P,
     choice1: Choice,
-    player2: User,
+    player2: P,
     choice2: Choice,
 }
 
-impl Battle {
-    pub fn new(player1: User, choice1: Choice, player2: User, choice2: Choice) -> Self {
+impl<P: Player> Battle<P> {
+    pub fn new(player1: P, choice1: Choice, player2: P, choice2: Choice) -> Self {
         Battle {
             player1,
             choice1,
@@ -17,10 +16,10 @@ impl Battle {
             choice2,
         }
     }
-    pub fn new_ref(player1: &User, choice1: &Choice, player2: &User, choice2: &Choice) -> Self {
+    pub fn new_ref(player1: &P, choice1: &Choice, player2: &P, choice2: &Choice) -> Self {
         Self::new(player1.clone(), *choice1, player2.clone(), *choice2)
     }
-    pub fn battle(&self) -> Option<BattleResult> {
+    pub fn battle(&self) -> Option<BattleResult<P>> {
         match self.choice1 {
             Choice::Rock => match self.choice2 {
                 Choice::Rock => None,
@@ -59,7 +58,7 @@ impl Battle {
             },
         }
     }
-    fn win1(&self, verb: &str) -> BattleResult {
+    fn win1(&self, verb: &str) -> BattleResult<P> {
         BattleResult::new(
             self.player1.clone(),
             self.choice1,
@@ -68,7 +67,7 @@ impl Battle {
             verb,
         )
     }
-    fn win2(&self, verb: &str) -> BattleResult {
+    fn win2(&self, verb: &str) -> BattleResult<P> {
         BattleResult::new(
             self.player2.clone(),
             self.choice2,
@@ -0,0 +1,28 @@
+use crate::choice::Choice;
+use crate::player::Player;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BattleResult<P: Player> {
+    pub winner: P,
+    pub winner_choice: Choice,
+    pub loser: P,
+    pub loser_choice: Choice,
+    pub verb: String,
+}
+impl<P: Player> BattleResult<P> {
+    pub fn new(
+        winner: P,
+        winner_choice: Choice,
+        loser: P,
+        loser_choice: Choice,
+        verb: &str,
+    ) -> Self {
+        BattleResult {
+            winner,
+            winner_choice,
+            loser,
+            loser_choice,
+            verb: verb.to_string(),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rps_core::simulation::{default_strategies, round_robin};
+
+fn main() {
+    let args: Vec<u64> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    let games = *args.first().unwrap_or(&1000);
+    let rounds = *args.get(1).unwrap_or(&10);
+    let mut rng = match args.get(2) {
+        Some(seed) => StdRng::seed_from_u64(*seed),
+        None => StdRng::from_entropy(),
+    };
+    println!(
+        "Win rate of the row strategy against the column strategy ({} games of {} rounds)",
+        games, rounds
+    );
+    print!(
+        "{}",
+        round_robin(&mut rng, &default_strategies(), games, rounds)
+    );
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Choice {
+    Rock,
+    Paper,
+    Scissors,
+    Lizard,
+    Spock,
+}
+
+impl Choice {
+    pub fn all() -> [Choice; 5] {
+        [
+            Choice::Rock,
+            Choice::Paper,
+            Choice::Scissors,
+            Choice::Lizard,
+            Choice::Spock,
+        ]
+    }
+    pub fn from_char(c: char) -> Option<Choice> {
+        match c {
+            'r' => Some(Choice::Rock),
+            'p' => Some(Choice::Paper),
+            's' => Some(Choice::Scissors),
+            'l' => Some(Choice::Lizard),
+            'S' => Some(Choice::Spock),
+            _ => None,
+        }
+    }
+    pub fn to_char(&self) -> char {
+        match self {
+            Choice::Rock => 'r',
+            Choice::Paper => 'p',
+            Choice::Scissors => 's',
+            Choice::Lizard => 'l',
+            Choice::Spock => 'S',
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Choice> {
+        Choice::all()
+            .into_iter()
+            .find(|choice| choice.name().eq_ignore_ascii_case(name))
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Choice::Rock => "Rock",
+            Choice::Paper => "Paper",
+            Choice::Scissors => "Scissors",
+            Choice::Lizard => "Lizard",
+            Choice::Spock => "Spock",
+        }
+    }
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Choice::Rock => "🪨",
+            Choice::Paper => "📄",
+            Choice::Scissors => "✂️",
+            Choice::Lizard => "🦎",
+            Choice::Spock => "🖖",
+        }
+    }
+}
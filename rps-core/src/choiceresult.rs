@@ -0,0 +1,29 @@
+use crate::choice::Choice;
+
+/// The outcome between everyone who picked `winner_choice` and everyone who picked
+/// `loser_choice` in one round. Each of the `winners` beats each of the `losers`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ChoiceResult {
+    pub winner_choice: Choice,
+    pub winners: usize,
+    pub loser_choice: Choice,
+    pub losers: usize,
+    pub verb: String,
+}
+impl ChoiceResult {
+    pub fn new(
+        winner_choice: Choice,
+        winners: usize,
+        loser_choice: Choice,
+        losers: usize,
+        verb: &str,
+    ) -> Self {
+        ChoiceResult {
+            winner_choice,
+            winners,
+            loser_choice,
+            losers,
+            verb: verb.to_string(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::choice::Choice;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A change to a [`Game`](crate::game::Game). The state of a game is the result of applying
+/// its events in order, so the event log is enough to rebuild or replay it.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum GameEvent<P> {
+    PlayerJoined {
+        player: P,
+    },
+    PlayerLeft {
+        player: P,
+    },
+    GameStarted,
+    ChoiceMade {
+        player: P,
+        choice: Choice,
+    },
+    /// The choices made since the last round were scored.
+    RoundResolved,
+    GameFinished {
+        cancelled: bool,
+    },
+}
+
+impl<P> GameEvent<P> {
+    /// Converts the players of the event, for example to store only their ids.
+    pub fn map_player<Q>(&self, f: impl Fn(&P) -> Q) -> GameEvent<Q> {
+        match self {
+            GameEvent::PlayerJoined { player } => GameEvent::PlayerJoined { player: f(player) },
+            GameEvent::PlayerLeft { player } => GameEvent::PlayerLeft { player: f(player) },
+            GameEvent::GameStarted => GameEvent::GameStarted,
+            GameEvent::ChoiceMade { player, choice } => GameEvent::ChoiceMade {
+                player: f(player),
+                choice: *choice,
+            },
+            GameEvent::RoundResolved => GameEvent::RoundResolved,
+            GameEvent::GameFinished { cancelled } => GameEvent::GameFinished {
+                cancelled: *cancelled,
+            },
+        }
+    }
+}
+
+/// A [`GameEvent`] with the time it happened, in milliseconds since the Unix epoch.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct LoggedEvent<P> {
+    pub at: u64,
+    #[serde(flatten)]
+    pub event: GameEvent<P>,
+}
+
+impl<P> LoggedEvent<P> {
+    pub fn now(event: GameEvent<P>) -> Self {
+        LoggedEvent {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis() as u64),
+            event,
+        }
+    }
+    pub fn map_player<Q>(&self, f: impl Fn(&P) -> Q) -> LoggedEvent<Q> {
+        LoggedEvent {
+            at: self.at,
+            event: self.event.map_player(f),
+        }
+    }
+}
@@ -0,0 +1,468 @@
+use crate::battle::Battle;
+use crate::choice::Choice;
+use crate::choiceresult::ChoiceResult;
+use crate::event::{GameEvent, LoggedEvent};
+use crate::outcome::{CyclicPolicy, RoundOutcome};
+use crate::player::Player;
+use crate::round::Round;
+use crate::ruleset::RuleSet;
+use crate::state::{GameError, GameState};
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+
+/// How many players a game needs before it can start.
+pub const MIN_PLAYERS: usize = 2;
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct Game<P: Player> {
+    state: GameState,
+    round: u64,
+    rounds: u64,
+    id: String,
+    rule_set: RuleSet,
+    cyclic_policy: CyclicPolicy,
+    players: HashSet<P>,
+    choices: HashMap<P, Choice>,
+    points: HashMap<P, u64>,
+    history: Vec<Round<P>>,
+    events: Vec<LoggedEvent<P>>,
+}
+
+impl<P: Player> Game<P> {
+    pub fn new(id: String, rule_set: RuleSet, rounds: u64) -> Self {
+        Game {
+            state: GameState::Lobby,
+            round: 0,
+            rounds,
+            id,
+            rule_set,
+            cyclic_policy: CyclicPolicy::default(),
+            players: HashSet::new(),
+            choices: HashMap::new(),
+            points: HashMap::new(),
+            history: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+    pub fn get_player_count(&self) -> usize {
+        self.players.len()
+    }
+    /// Rebuilds a game by applying `events` to a new game with the given settings.
+    pub fn from_events(
+        id: String,
+        rule_set: RuleSet,
+        rounds: u64,
+        cyclic_policy: CyclicPolicy,
+        events: impl IntoIterator<Item = LoggedEvent<P>>,
+    ) -> Result<Self, GameError> {
+        let mut game = Game::new(id, rule_set, rounds);
+        game.set_cyclic_policy(cyclic_policy);
+        for event in events {
+            game.record(event)?;
+        }
+        Ok(game)
+    }
+    pub fn add_player(&mut self, player: &P) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::PlayerJoined {
+            player: player.clone(),
+        }))
+    }
+    /// Removes `player` from the lobby.
+    pub fn remove_player(&mut self, player: &P) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::PlayerLeft {
+            player: player.clone(),
+        }))
+    }
+    /// Closes the lobby and starts the first round.
+    pub fn start(&mut self) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::GameStarted))
+    }
+    /// Ends the game before every round was played.
+    pub fn cancel(&mut self) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::GameFinished {
+            cancelled: true,
+        }))
+    }
+    pub fn get_finished_players(&self) -> usize {
+        self.choices.keys().count()
+    }
+    pub fn did_all_choose(&self) -> bool {
+        self.get_player_count() == self.get_finished_players()
+    }
+    /// Records the pick of `player` for the current round. A player can change their pick
+    /// until the round is resolved.
+    pub fn choose(&mut self, player: &P, choice: Choice) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::ChoiceMade {
+            player: player.clone(),
+            choice,
+        }))
+    }
+    /// Compares every pair of choices picked this round once, counting how many players
+    /// picked each, instead of pitting every pair of players against each other.
+    pub fn get_choice_results(&self) -> Vec<ChoiceResult> {
+        let counts = self.choices.values().counts();
+        Choice::all()
+            .into_iter()
+            .filter_map(|choice| Some((choice, *counts.get(&choice)?)))
+            .tuple_combinations()
+            .filter_map(|((choice1, count1), (choice2, count2))| {
+                let result = Battle::new(count1, choice1, count2, choice2).battle()?;
+                Some(ChoiceResult::new(
+                    result.winner_choice,
+                    result.winner,
+                    result.loser_choice,
+                    result.loser,
+                    &result.verb,
+                ))
+            })
+            .collect()
+    }
+    pub fn get_outcome(&self) -> RoundOutcome {
+        RoundOutcome::classify(self.choices.values().copied(), self.rule_set)
+    }
+    /// Scores the choices of this round and moves on to the next one, unless the round was
+    /// a cyclic draw that the [`CyclicPolicy`] says to replay. Finishes the game after the
+    /// last round.
+    pub fn resolve_round(&mut self) -> Result<(), GameError> {
+        self.record(LoggedEvent::now(GameEvent::RoundResolved))?;
+        if self.round >= self.rounds {
+            self.record(LoggedEvent::now(GameEvent::GameFinished {
+                cancelled: false,
+            }))?;
+        }
+        Ok(())
+    }
+    /// Applies `event` and adds it to the log, or returns why it is not allowed in the
+    /// current state.
+    fn record(&mut self, event: LoggedEvent<P>) -> Result<(), GameError> {
+        self.check(&event.event)?;
+        self.apply(&event.event);
+        self.events.push(event);
+        Ok(())
+    }
+    fn check(&self, event: &GameEvent<P>) -> Result<(), GameError> {
+        match (self.state, event) {
+            (state, _) if state.is_over() => Err(GameError::GameOver),
+            (GameState::Lobby, GameEvent::PlayerJoined { player })
+                if self.players.contains(player) =>
+            {
+                Err(GameError::AlreadyJoined)
+            }
+            (GameState::Lobby, GameEvent::PlayerLeft { player })
+                if !self.players.contains(player) =>
+            {
+                Err(GameError::NotAPlayer)
+            }
+            (GameState::Lobby, GameEvent::GameStarted) if self.players.len() < MIN_PLAYERS => {
+                Err(GameError::NotEnoughPlayers)
+            }
+            (
+                GameState::Lobby,
+                GameEvent::PlayerJoined { .. }
+                | GameEvent::PlayerLeft { .. }
+                | GameEvent::GameStarted
+                | GameEvent::GameFinished { cancelled: true },
+            ) => Ok(()),
+            (GameState::Lobby, _) => Err(GameError::NotStarted),
+            (_, GameEvent::ChoiceMade { player, .. }) if !self.players.contains(player) => {
+                Err(GameError::NotAPlayer)
+            }
+            (_, GameEvent::ChoiceMade { choice, .. }) if !self.rule_set.allows(*choice) => {
+                Err(GameError::ChoiceNotAllowed(*choice))
+            }
+            (_, GameEvent::ChoiceMade { .. } | GameEvent::RoundResolved) => Ok(()),
+            (_, GameEvent::GameFinished { cancelled })
+                if *cancelled || self.round >= self.rounds =>
+            {
+                Ok(())
+            }
+            (_, GameEvent::GameFinished { .. }) => Err(GameError::NotFinished),
+            (_, _) => Err(GameError::AlreadyStarted),
+        }
+    }
+    fn apply(&mut self, event: &GameEvent<P>) {
+        match event {
+            GameEvent::PlayerJoined { player } => {
+                self.players.insert(player.clone());
+                self.points.insert(player.clone(), 0);
+            }
+            GameEvent::PlayerLeft { player } => {
+                self.players.remove(player);
+                self.points.remove(player);
+            }
+            GameEvent::GameStarted => self.state = GameState::Choosing,
+            GameEvent::ChoiceMade { player, choice } => {
+                self.choices.insert(player.clone(), *choice);
+                self.state = GameState::Choosing;
+            }
+            GameEvent::RoundResolved => self.score_round(),
+            GameEvent::GameFinished { cancelled } => {
+                self.state = match cancelled {
+                    true => GameState::Cancelled,
+                    false => GameState::Finished,
+                }
+            }
+        }
+    }
+    fn score_round(&mut self) {
+        let outcome = self.get_outcome();
+        let results = match (outcome, self.cyclic_policy) {
+            (RoundOutcome::CyclicDraw, CyclicPolicy::Replay | CyclicPolicy::NoPoints) => vec![],
+            _ => self.get_choice_results(),
+        };
+        let choices = self.choices.drain().collect();
+        let round = Round::new(self.get_round(), choices, results, outcome);
+        round
+            .points
+            .iter()
+            .for_each(|(player, points)| self.add_points(player, *points));
+        self.history.push(round);
+        if !(outcome == RoundOutcome::CyclicDraw && self.cyclic_policy == CyclicPolicy::Replay) {
+            self.round += 1;
+        }
+        self.state = GameState::Revealing;
+    }
+    pub fn get_state(&self) -> GameState {
+        self.state
+    }
+    /// Whether every round was played.
+    pub fn is_done(&self) -> bool {
+        self.state == GameState::Finished
+    }
+    fn add_points(&mut self, player: &P, points: u64) {
+        self.points.insert(
+            player.clone(),
+            *self.points.get(player).unwrap_or(&0) + points,
+        );
+    }
+    pub fn get_round(&self) -> u64 {
+        self.round + 1
+    }
+    pub fn get_rounds(&self) -> u64 {
+        self.rounds
+    }
+    pub fn get_id(&self) -> &String {
+        &self.id
+    }
+    pub fn get_rule_set(&self) -> RuleSet {
+        self.rule_set
+    }
+    pub fn get_cyclic_policy(&self) -> CyclicPolicy {
+        self.cyclic_policy
+    }
+    pub fn set_cyclic_policy(&mut self, cyclic_policy: CyclicPolicy) {
+        self.cyclic_policy = cyclic_policy;
+    }
+    pub fn get_players(&self) -> &HashSet<P> {
+        &self.players
+    }
+    pub fn get_points(&self) -> &HashMap<P, u64> {
+        &self.points
+    }
+    pub fn get_history(&self) -> &Vec<Round<P>> {
+        &self.history
+    }
+    pub fn get_events(&self) -> &Vec<LoggedEvent<P>> {
+        &self.events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+
+    fn play(policy: CyclicPolicy, choices: &[Choice]) -> Game<usize> {
+        let mut game = Game::new("test".to_string(), RuleSet::Classic, 3);
+        game.set_cyclic_policy(policy);
+        (0..choices.len()).for_each(|player| game.add_player(&player).unwrap());
+        game.start().unwrap();
+        choices
+            .iter()
+            .enumerate()
+            .for_each(|(player, choice)| game.choose(&player, *choice).unwrap());
+        game.resolve_round().unwrap();
+        game
+    }
+
+    const CYCLE: [Choice; 4] = [Choice::Rock, Choice::Rock, Choice::Paper, Choice::Scissors];
+
+    #[test]
+    fn unanimous_round_awards_no_points() {
+        let game = play(CyclicPolicy::Pairwise, &[Choice::Paper; 3]);
+        assert!(game.get_points().values().all(|points| *points == 0));
+        assert_eq!(game.get_round(), 2);
+    }
+
+    #[test]
+    fn normal_round_awards_a_point_per_opponent_beaten() {
+        let game = play(
+            CyclicPolicy::NoPoints,
+            &[Choice::Rock, Choice::Rock, Choice::Scissors],
+        );
+        assert_eq!(game.get_points()[&0], 1);
+        assert_eq!(game.get_points()[&1], 1);
+        assert_eq!(game.get_points()[&2], 0);
+    }
+
+    #[test]
+    fn cyclic_draw_without_points() {
+        let game = play(CyclicPolicy::NoPoints, &CYCLE);
+        assert!(game.get_points().values().all(|points| *points == 0));
+        assert_eq!(game.get_round(), 2);
+        assert_eq!(game.get_history()[0].outcome, RoundOutcome::CyclicDraw);
+    }
+
+    #[test]
+    fn cyclic_draw_replays_the_round() {
+        let game = play(CyclicPolicy::Replay, &CYCLE);
+        assert!(game.get_points().values().all(|points| *points == 0));
+        assert_eq!(game.get_round(), 1);
+        assert_eq!(game.get_history().len(), 1);
+    }
+
+    #[test]
+    fn cyclic_draw_pairwise() {
+        let game = play(CyclicPolicy::Pairwise, &CYCLE);
+        assert_eq!(game.get_points()[&0], 1);
+        assert_eq!(game.get_points()[&1], 1);
+        assert_eq!(game.get_points()[&2], 2);
+        assert_eq!(game.get_points()[&3], 1);
+        assert_eq!(game.get_round(), 2);
+    }
+
+    #[test]
+    fn round_scores_match_pairwise_battles() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            let players = rng.gen_range(2..30);
+            let choices = (0..players)
+                .map(|_| *Choice::all().choose(&mut rng).unwrap())
+                .collect::<Vec<_>>();
+            let mut game = Game::new("test".to_string(), RuleSet::LizardSpock, 1);
+            game.set_cyclic_policy(CyclicPolicy::Pairwise);
+            (0..players).for_each(|player| game.add_player(&player).unwrap());
+            game.start().unwrap();
+            for (player, choice) in choices.iter().enumerate() {
+                game.choose(&player, *choice).unwrap();
+            }
+            game.resolve_round().unwrap();
+            let round = &game.get_history()[0];
+            let mut wins = vec![0; players];
+            let mut losses = vec![0; players];
+            for (player1, player2) in (0..players).tuple_combinations() {
+                let battle = Battle::new(player1, choices[player1], player2, choices[player2]);
+                if let Some(result) = battle.battle() {
+                    wins[result.winner] += 1;
+                    losses[result.loser] += 1;
+                }
+            }
+            for player in 0..players {
+                assert_eq!(round.wins(&player), wins[player], "{:?}", choices);
+                assert_eq!(round.losses(&player), losses[player], "{:?}", choices);
+                assert_eq!(
+                    round.points.get(&player).copied().unwrap_or(0),
+                    wins[player] as u64,
+                    "{:?}",
+                    choices
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lobby_transitions() {
+        let mut game = Game::new("test".to_string(), RuleSet::Classic, 1);
+        assert_eq!(game.choose(&0, Choice::Rock), Err(GameError::NotStarted));
+        assert_eq!(game.resolve_round(), Err(GameError::NotStarted));
+        game.add_player(&0).unwrap();
+        assert_eq!(game.add_player(&0), Err(GameError::AlreadyJoined));
+        assert_eq!(game.start(), Err(GameError::NotEnoughPlayers));
+        game.add_player(&1).unwrap();
+        game.start().unwrap();
+        assert_eq!(game.get_state(), GameState::Choosing);
+        assert_eq!(game.start(), Err(GameError::AlreadyStarted));
+        assert_eq!(game.add_player(&2), Err(GameError::AlreadyStarted));
+    }
+
+    #[test]
+    fn round_transitions() {
+        let mut game = Game::new("test".to_string(), RuleSet::Classic, 2);
+        game.add_player(&0).unwrap();
+        game.add_player(&1).unwrap();
+        game.start().unwrap();
+        assert_eq!(game.choose(&2, Choice::Rock), Err(GameError::NotAPlayer));
+        assert_eq!(
+            game.choose(&0, Choice::Spock),
+            Err(GameError::ChoiceNotAllowed(Choice::Spock))
+        );
+        game.resolve_round().unwrap();
+        assert_eq!(game.get_state(), GameState::Revealing);
+        game.choose(&0, Choice::Rock).unwrap();
+        assert_eq!(game.get_state(), GameState::Choosing);
+        game.resolve_round().unwrap();
+        assert_eq!(game.get_state(), GameState::Finished);
+        assert_eq!(game.choose(&0, Choice::Rock), Err(GameError::GameOver));
+        assert_eq!(game.cancel(), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn cancel_ends_a_running_game() {
+        let mut game = Game::<usize>::new("test".to_string(), RuleSet::Classic, 2);
+        game.cancel().unwrap();
+        assert_eq!(game.get_state(), GameState::Cancelled);
+        assert!(!game.is_done());
+        assert_eq!(game.add_player(&0), Err(GameError::GameOver));
+    }
+
+    #[test]
+    fn events_rebuild_the_game() {
+        let mut game = Game::new("test".to_string(), RuleSet::Classic, 2);
+        game.set_cyclic_policy(CyclicPolicy::Replay);
+        game.add_player(&0).unwrap();
+        game.add_player(&1).unwrap();
+        game.add_player(&2).unwrap();
+        game.remove_player(&2).unwrap();
+        game.start().unwrap();
+        game.choose(&0, Choice::Rock).unwrap();
+        game.choose(&0, Choice::Paper).unwrap();
+        game.choose(&1, Choice::Rock).unwrap();
+        game.resolve_round().unwrap();
+        game.choose(&1, Choice::Scissors).unwrap();
+        game.resolve_round().unwrap();
+        assert!(game.is_done());
+        assert!(matches!(
+            game.get_events().last().map(|event| &event.event),
+            Some(GameEvent::GameFinished { cancelled: false })
+        ));
+        let rebuilt = Game::from_events(
+            "test".to_string(),
+            RuleSet::Classic,
+            2,
+            CyclicPolicy::Replay,
+            game.get_events().clone(),
+        )
+        .unwrap();
+        assert!(rebuilt == game);
+        assert_eq!(rebuilt.get_points()[&0], 1);
+        assert_eq!(rebuilt.get_points()[&1], 0);
+    }
+
+    #[test]
+    fn illegal_events_are_rejected() {
+        let events = [
+            GameEvent::PlayerJoined { player: 0 },
+            GameEvent::RoundResolved,
+        ];
+        let game = Game::from_events(
+            "test".to_string(),
+            RuleSet::Classic,
+            1,
+            CyclicPolicy::default(),
+            events.into_iter().map(LoggedEvent::now),
+        );
+        assert!(game.err() == Some(GameError::NotStarted));
+    }
+}
@@ -0,0 +1,12 @@
+pub mod battle;
+pub mod battleresult;
+pub mod choice;
+pub mod choiceresult;
+pub mod event;
+pub mod game;
+pub mod outcome;
+pub mod player;
+pub mod round;
+pub mod ruleset;
+pub mod simulation;
+pub mod state;
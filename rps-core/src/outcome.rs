@@ -0,0 +1,120 @@
+use crate::choice::Choice;
+use crate::ruleset::RuleSet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What kind of round was played, decided only by which choices were picked.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RoundOutcome {
+    /// Everyone picked the same choice, so no one wins.
+    Unanimous(Choice),
+    /// Every choice of the rule set was picked, so every choice beats some and loses to
+    /// others and no one is meaningfully ahead.
+    CyclicDraw,
+    Normal,
+}
+
+impl RoundOutcome {
+    pub fn classify(choices: impl IntoIterator<Item = Choice>, rule_set: RuleSet) -> Self {
+        let picked: HashSet<Choice> = choices.into_iter().collect();
+        if picked.len() == 1 {
+            if let Some(choice) = picked.into_iter().next() {
+                return RoundOutcome::Unanimous(choice);
+            }
+        } else if rule_set
+            .choices()
+            .iter()
+            .all(|choice| picked.contains(choice))
+        {
+            return RoundOutcome::CyclicDraw;
+        }
+        RoundOutcome::Normal
+    }
+}
+
+/// How a game scores a [`RoundOutcome::CyclicDraw`].
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CyclicPolicy {
+    /// Play the round again without awarding points.
+    Replay,
+    /// Count the round but award no points.
+    #[default]
+    NoPoints,
+    /// Award points for every opponent beaten, like in any other round.
+    Pairwise,
+}
+
+impl CyclicPolicy {
+    pub fn all() -> [CyclicPolicy; 3] {
+        [
+            CyclicPolicy::Replay,
+            CyclicPolicy::NoPoints,
+            CyclicPolicy::Pairwise,
+        ]
+    }
+    pub fn from_name(name: &str) -> Option<CyclicPolicy> {
+        CyclicPolicy::all()
+            .into_iter()
+            .find(|policy| policy.name().eq_ignore_ascii_case(name))
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            CyclicPolicy::Replay => "replay",
+            CyclicPolicy::NoPoints => "no-points",
+            CyclicPolicy::Pairwise => "pairwise",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanimous_regardless_of_order() {
+        let choices = [Choice::Rock, Choice::Rock, Choice::Rock];
+        assert_eq!(
+            RoundOutcome::classify(choices, RuleSet::Classic),
+            RoundOutcome::Unanimous(Choice::Rock)
+        );
+    }
+
+    #[test]
+    fn non_adjacent_duplicates_are_not_unanimous() {
+        let choices = [Choice::Rock, Choice::Paper, Choice::Rock, Choice::Paper];
+        assert_eq!(
+            RoundOutcome::classify(choices, RuleSet::Classic),
+            RoundOutcome::Normal
+        );
+    }
+
+    #[test]
+    fn every_choice_is_a_cyclic_draw() {
+        let choices = [Choice::Scissors, Choice::Rock, Choice::Paper, Choice::Rock];
+        assert_eq!(
+            RoundOutcome::classify(choices, RuleSet::Classic),
+            RoundOutcome::CyclicDraw
+        );
+        assert_eq!(
+            RoundOutcome::classify(Choice::all(), RuleSet::LizardSpock),
+            RoundOutcome::CyclicDraw
+        );
+    }
+
+    #[test]
+    fn missing_choice_is_normal() {
+        let choices = [Choice::Rock, Choice::Paper, Choice::Scissors];
+        assert_eq!(
+            RoundOutcome::classify(choices, RuleSet::LizardSpock),
+            RoundOutcome::Normal
+        );
+    }
+
+    #[test]
+    fn policy_names_round_trip() {
+        CyclicPolicy::all()
+            .into_iter()
+            .for_each(|policy| assert_eq!(CyclicPolicy::from_name(policy.name()), Some(policy)));
+    }
+}
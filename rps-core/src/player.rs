@@ -0,0 +1,5 @@
+use std::hash::Hash;
+
+pub trait Player: Clone + Eq + Hash {}
+
+impl<T: Clone + Eq + Hash> Player for T {}
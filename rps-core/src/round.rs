@@ -0,0 +1,59 @@
+use crate::choice::Choice;
+use crate::choiceresult::ChoiceResult;
+use crate::outcome::RoundOutcome;
+use crate::player::Player;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Clone)]
+pub struct Round<P: Player> {
+    pub number: u64,
+    pub choices: HashMap<P, Choice>,
+    pub results: Vec<ChoiceResult>,
+    pub points: HashMap<P, u64>,
+    pub outcome: RoundOutcome,
+}
+
+impl<P: Player> Round<P> {
+    pub fn new(
+        number: u64,
+        choices: HashMap<P, Choice>,
+        results: Vec<ChoiceResult>,
+        outcome: RoundOutcome,
+    ) -> Self {
+        let mut choice_points = HashMap::new();
+        results.iter().for_each(|result| {
+            *choice_points.entry(result.winner_choice).or_insert(0) += result.losers as u64;
+        });
+        let points = choices
+            .iter()
+            .filter_map(|(player, choice)| Some((player.clone(), *choice_points.get(choice)?)))
+            .collect();
+        Round {
+            number,
+            choices,
+            results,
+            points,
+            outcome,
+        }
+    }
+    /// How many opponents `player` beat this round.
+    pub fn wins(&self, player: &P) -> usize {
+        self.choices.get(player).map_or(0, |choice| {
+            self.results
+                .iter()
+                .filter(|result| result.winner_choice == *choice)
+                .map(|result| result.losers)
+                .sum()
+        })
+    }
+    /// How many opponents beat `player` this round.
+    pub fn losses(&self, player: &P) -> usize {
+        self.choices.get(player).map_or(0, |choice| {
+            self.results
+                .iter()
+                .filter(|result| result.loser_choice == *choice)
+                .map(|result| result.winners)
+                .sum()
+        })
+    }
+}
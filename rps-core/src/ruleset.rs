@@ -0,0 +1,36 @@
+use crate::choice::Choice;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleSet {
+    Classic,
+    #[default]
+    LizardSpock,
+}
+
+impl RuleSet {
+    pub fn all() -> [RuleSet; 2] {
+        [RuleSet::Classic, RuleSet::LizardSpock]
+    }
+    pub fn from_name(name: &str) -> Option<RuleSet> {
+        RuleSet::all()
+            .into_iter()
+            .find(|rule_set| rule_set.name().eq_ignore_ascii_case(name))
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleSet::Classic => "classic",
+            RuleSet::LizardSpock => "lizard-spock",
+        }
+    }
+    pub fn choices(&self) -> Vec<Choice> {
+        match self {
+            RuleSet::Classic => vec![Choice::Rock, Choice::Paper, Choice::Scissors],
+            RuleSet::LizardSpock => Choice::all().to_vec(),
+        }
+    }
+    pub fn allows(&self, choice: Choice) -> bool {
+        self.choices().contains(&choice)
+    }
+}
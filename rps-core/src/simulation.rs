@@ -0,0 +1,218 @@
+use crate::battle::Battle;
+use crate::choice::Choice;
+use crate::game::Game;
+use crate::ruleset::RuleSet;
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use std::collections::HashMap;
+use std::fmt;
+
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+    fn choose(&mut self, rng: &mut dyn RngCore, own: &[Choice], opponent: &[Choice]) -> Choice;
+}
+
+pub type StrategyFactory = fn() -> Box<dyn Strategy>;
+
+fn random_choice(rng: &mut dyn RngCore) -> Choice {
+    *Choice::all().choose(rng).unwrap_or(&Choice::Rock)
+}
+
+fn counter(rng: &mut dyn RngCore, choice: Choice) -> Choice {
+    let counters = Choice::all()
+        .into_iter()
+        .filter(|candidate| {
+            Battle::new(0, *candidate, 1, choice)
+                .battle()
+                .is_some_and(|result| result.winner == 0)
+        })
+        .collect_vec();
+    *counters.choose(rng).unwrap_or(&choice)
+}
+
+fn most_common(choices: impl Iterator<Item = Choice>) -> Option<Choice> {
+    choices
+        .counts()
+        .into_iter()
+        .sorted_by_key(|(choice, _)| Choice::all().iter().position(|c| c == choice))
+        .max_by_key(|(_, count)| *count)
+        .map(|(choice, _)| choice)
+}
+
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+    fn choose(&mut self, rng: &mut dyn RngCore, _: &[Choice], _: &[Choice]) -> Choice {
+        random_choice(rng)
+    }
+}
+
+pub struct FrequencyStrategy;
+
+impl Strategy for FrequencyStrategy {
+    fn name(&self) -> &'static str {
+        "frequency"
+    }
+    fn choose(&mut self, rng: &mut dyn RngCore, _: &[Choice], opponent: &[Choice]) -> Choice {
+        match most_common(opponent.iter().copied()) {
+            Some(choice) => counter(rng, choice),
+            None => random_choice(rng),
+        }
+    }
+}
+
+pub struct MarkovStrategy;
+
+impl Strategy for MarkovStrategy {
+    fn name(&self) -> &'static str {
+        "markov"
+    }
+    fn choose(&mut self, rng: &mut dyn RngCore, _: &[Choice], opponent: &[Choice]) -> Choice {
+        let prediction = opponent.last().and_then(|last| {
+            most_common(
+                opponent
+                    .iter()
+                    .tuple_windows()
+                    .filter(|(previous, _)| *previous == last)
+                    .map(|(_, next)| *next),
+            )
+        });
+        match prediction {
+            Some(choice) => counter(rng, choice),
+            None => random_choice(rng),
+        }
+    }
+}
+
+pub struct CycleStrategy;
+
+impl Strategy for CycleStrategy {
+    fn name(&self) -> &'static str {
+        "cycle"
+    }
+    fn choose(&mut self, _: &mut dyn RngCore, own: &[Choice], _: &[Choice]) -> Choice {
+        Choice::all()[own.len() % Choice::all().len()]
+    }
+}
+
+pub struct CopycatStrategy;
+
+impl Strategy for CopycatStrategy {
+    fn name(&self) -> &'static str {
+        "copycat"
+    }
+    fn choose(&mut self, rng: &mut dyn RngCore, _: &[Choice], opponent: &[Choice]) -> Choice {
+        match opponent.last() {
+            Some(choice) => *choice,
+            None => random_choice(rng),
+        }
+    }
+}
+
+pub fn default_strategies() -> Vec<StrategyFactory> {
+    vec![
+        || Box::new(RandomStrategy),
+        || Box::new(FrequencyStrategy),
+        || Box::new(MarkovStrategy),
+        || Box::new(CycleStrategy),
+        || Box::new(CopycatStrategy),
+    ]
+}
+
+pub enum MatchOutcome {
+    First,
+    Second,
+    Draw,
+}
+
+pub fn play_match(
+    rng: &mut dyn RngCore,
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    rounds: u64,
+) -> MatchOutcome {
+    let mut game = Game::new("simulation".to_string(), RuleSet::LizardSpock, rounds);
+    game.add_player(&0u8).expect("empty lobby");
+    game.add_player(&1u8).expect("new player");
+    game.start().expect("two players");
+    let mut first_choices = vec![];
+    let mut second_choices = vec![];
+    while !game.is_done() {
+        let first_choice = first.choose(rng, &first_choices, &second_choices);
+        let second_choice = second.choose(rng, &second_choices, &first_choices);
+        game.choose(&0, first_choice).expect("running game");
+        game.choose(&1, second_choice).expect("running game");
+        game.resolve_round().expect("running game");
+        first_choices.push(first_choice);
+        second_choices.push(second_choice);
+    }
+    let points = game.get_points();
+    match points.get(&0).cmp(&points.get(&1)) {
+        std::cmp::Ordering::Greater => MatchOutcome::First,
+        std::cmp::Ordering::Less => MatchOutcome::Second,
+        std::cmp::Ordering::Equal => MatchOutcome::Draw,
+    }
+}
+
+pub struct WinRateMatrix {
+    names: Vec<&'static str>,
+    wins: HashMap<(usize, usize), u64>,
+    games: u64,
+}
+
+impl WinRateMatrix {
+    pub fn win_rate(&self, first: usize, second: usize) -> f64 {
+        *self.wins.get(&(first, second)).unwrap_or(&0) as f64 / self.games as f64
+    }
+}
+
+impl fmt::Display for WinRateMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>10}", "")?;
+        for name in &self.names {
+            write!(f, " {:>10}", name)?;
+        }
+        writeln!(f)?;
+        for (first, name) in self.names.iter().enumerate() {
+            write!(f, "{:>10}", name)?;
+            for second in 0..self.names.len() {
+                write!(f, " {:>9.1}%", self.win_rate(first, second) * 100.0)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn round_robin(
+    rng: &mut dyn RngCore,
+    strategies: &[StrategyFactory],
+    games: u64,
+    rounds: u64,
+) -> WinRateMatrix {
+    let mut wins = HashMap::new();
+    for (first, first_factory) in strategies.iter().enumerate() {
+        for (second, second_factory) in strategies.iter().enumerate() {
+            for _ in 0..games {
+                let outcome = play_match(
+                    rng,
+                    first_factory().as_mut(),
+                    second_factory().as_mut(),
+                    rounds,
+                );
+                if let MatchOutcome::First = outcome {
+                    *wins.entry((first, second)).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+    WinRateMatrix {
+        names: strategies.iter().map(|factory| factory().name()).collect(),
+        wins,
+        games,
+    }
+}
@@ -0,0 +1,73 @@
+use crate::choice::Choice;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The phase a game is in. Every change of phase goes through a method of
+/// [`Game`](crate::game::Game) that checks the action is allowed in the current one.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GameState {
+    /// Players can join, and the game can be started once enough have.
+    Lobby,
+    /// The players are picking their weapons for the current round.
+    Choosing,
+    /// A round was resolved and its results are shown until the first pick of the next one.
+    Revealing,
+    /// Every round was played.
+    Finished,
+    /// The game was ended before every round was played.
+    Cancelled,
+}
+
+impl GameState {
+    pub fn is_over(&self) -> bool {
+        matches!(self, GameState::Finished | GameState::Cancelled)
+    }
+}
+
+/// An action that is not allowed in the current [`GameState`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameError {
+    AlreadyJoined,
+    AlreadyStarted,
+    NotEnoughPlayers,
+    NotStarted,
+    NotAPlayer,
+    ChoiceNotAllowed(Choice),
+    NotFinished,
+    GameOver,
+}
+
+impl GameError {
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameError::AlreadyJoined => "already-joined",
+            GameError::AlreadyStarted => "already-started",
+            GameError::NotEnoughPlayers => "not-enough-players",
+            GameError::NotStarted => "not-started",
+            GameError::NotAPlayer => "not-a-player",
+            GameError::ChoiceNotAllowed(_) => "choice-not-allowed",
+            GameError::NotFinished => "not-finished",
+            GameError::GameOver => "game-over",
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::AlreadyJoined => write!(f, "the player already joined"),
+            GameError::AlreadyStarted => write!(f, "the game has already started"),
+            GameError::NotEnoughPlayers => write!(f, "not enough players to start"),
+            GameError::NotStarted => write!(f, "the game has not started yet"),
+            GameError::NotAPlayer => write!(f, "the player is not part of the game"),
+            GameError::ChoiceNotAllowed(choice) => {
+                write!(f, "{} is not part of this rule set", choice.name())
+            }
+            GameError::NotFinished => write!(f, "not every round was played"),
+            GameError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
@@ -0,0 +1,327 @@
+use crate::helper::choice_to_emoji;
+use crate::i18n::Language;
+use itertools::Itertools;
+use rps_core::choice::Choice;
+use rps_core::round::Round;
+use serde::{Deserialize, Serialize};
+use serenity::builder::CreateEmbed;
+use serenity::model::id::UserId;
+use serenity::model::mention::Mentionable;
+use serenity::model::user::User;
+use serenity::utils::MessageBuilder;
+use std::collections::HashMap;
+
+const BAR_WIDTH: usize = 10;
+/// How many picks are needed before the predictability is shown. With fewer picks nearly
+/// every player looks predictable.
+const MIN_PREDICTABILITY_PICKS: usize = 10;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pick {
+    pub game_id: String,
+    pub round: u64,
+    pub choice: Choice,
+    pub outcome: Outcome,
+}
+
+pub fn picks_from_round(game_id: &str, round: &Round<User>) -> Vec<(UserId, Pick)> {
+    round
+        .choices
+        .iter()
+        .map(|(user, choice)| {
+            let outcome = match round.wins(user).cmp(&round.losses(user)) {
+                std::cmp::Ordering::Greater => Outcome::Win,
+                std::cmp::Ordering::Less => Outcome::Loss,
+                std::cmp::Ordering::Equal => Outcome::Draw,
+            };
+            (
+                user.id,
+                Pick {
+                    game_id: game_id.to_string(),
+                    round: round.number,
+                    choice: *choice,
+                    outcome,
+                },
+            )
+        })
+        .collect()
+}
+
+fn entropy<'a>(counts: impl Iterator<Item = &'a usize>) -> f64 {
+    let counts = counts.copied().filter(|count| *count > 0).collect_vec();
+    let total = counts.iter().sum::<usize>() as f64;
+    counts
+        .into_iter()
+        .map(|count| count as f64 / total)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+fn bar(share: f64) -> String {
+    let filled = (share * BAR_WIDTH as f64).round() as usize;
+    format!(
+        "{}{}",
+        "█".repeat(filled.min(BAR_WIDTH)),
+        "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH))
+    )
+}
+
+pub struct Analysis {
+    total: usize,
+    distribution: HashMap<Choice, usize>,
+    transitions: HashMap<(Choice, Choice), usize>,
+    after_win: (usize, usize),
+    after_loss: (usize, usize),
+}
+
+impl Analysis {
+    pub fn new(picks: &[Pick]) -> Self {
+        let mut distribution = HashMap::new();
+        picks.iter().for_each(|pick| {
+            *distribution.entry(pick.choice).or_insert(0) += 1;
+        });
+        let mut transitions = HashMap::new();
+        let mut after_win = (0, 0);
+        let mut after_loss = (0, 0);
+        picks
+            .iter()
+            .tuple_windows()
+            .filter(|(previous, next)| previous.game_id == next.game_id)
+            .for_each(|(previous, next)| {
+                *transitions
+                    .entry((previous.choice, next.choice))
+                    .or_insert(0) += 1;
+                let stayed = previous.choice == next.choice;
+                let counter = match previous.outcome {
+                    Outcome::Win => &mut after_win,
+                    Outcome::Loss => &mut after_loss,
+                    Outcome::Draw => return,
+                };
+                if stayed {
+                    counter.0 += 1;
+                } else {
+                    counter.1 += 1;
+                }
+            });
+        Analysis {
+            total: picks.len(),
+            distribution,
+            transitions,
+            after_win,
+            after_loss,
+        }
+    }
+    /// How well the next pick can be guessed from the picks so far, from 0 for random picks
+    /// to 1. Both the share of each choice and the pick that followed each choice are taken
+    /// into account. Returns `None` if there are too few picks to tell.
+    pub fn predictability(&self) -> Option<f64> {
+        if self.total < MIN_PREDICTABILITY_PICKS {
+            return None;
+        }
+        let max_entropy = (Choice::all().len() as f64).log2();
+        let marginal = entropy(self.distribution.values());
+        let transition_total = self.transitions.values().sum::<usize>();
+        let conditional = match transition_total {
+            0 => marginal,
+            _ => Choice::all()
+                .into_iter()
+                .map(|from| {
+                    let counts = self
+                        .transitions
+                        .iter()
+                        .filter(|((previous, _), _)| *previous == from)
+                        .map(|(_, count)| count)
+                        .collect_vec();
+                    let share =
+                        counts.iter().copied().sum::<usize>() as f64 / transition_total as f64;
+                    share * entropy(counts.into_iter())
+                })
+                .sum(),
+        };
+        Some(1.0 - marginal.min(conditional) / max_entropy)
+    }
+    fn distribution_chart(&self) -> String {
+        let mut msg = MessageBuilder::new();
+        Choice::all().into_iter().for_each(|choice| {
+            let count = *self.distribution.get(&choice).unwrap_or(&0);
+            let share = count as f64 / self.total as f64;
+            msg.push(choice_to_emoji(choice)).push(format!(
+                " `{} {:>3.0}%` ({})\n",
+                bar(share),
+                share * 100.0,
+                count
+            ));
+        });
+        msg.build()
+    }
+    fn transition_list(&self, language: Language) -> String {
+        let lines = Choice::all()
+            .into_iter()
+            .filter_map(|from| {
+                let total = self
+                    .transitions
+                    .iter()
+                    .filter(|((previous, _), _)| *previous == from)
+                    .map(|(_, count)| count)
+                    .sum::<usize>();
+                let (to, count) = self
+                    .transitions
+                    .iter()
+                    .filter(|((previous, _), _)| *previous == from)
+                    .max_by_key(|(_, count)| **count)
+                    .map(|((_, next), count)| (*next, *count))?;
+                Some((from, to, count, total))
+            })
+            .sorted_by_key(|(_, _, count, _)| std::cmp::Reverse(*count))
+            .map(|(from, to, count, total)| {
+                language.format(
+                    "analysis-transition",
+                    &[
+                        ("from", from.emoji().into()),
+                        ("to", to.emoji().into()),
+                        (
+                            "share",
+                            format!("{:.0}", count as f64 / total as f64 * 100.0).into(),
+                        ),
+                        ("count", count.into()),
+                        ("total", total.into()),
+                    ],
+                ) + "\n"
+            })
+            .collect::<String>();
+        match lines.is_empty() {
+            true => language.text("analysis-not-enough"),
+            false => lines,
+        }
+    }
+    fn reaction(counts: (usize, usize), language: Language) -> String {
+        let total = counts.0 + counts.1;
+        if total == 0 {
+            return language.text("analysis-not-enough");
+        }
+        let stay = counts.0 as f64 / total as f64;
+        format!(
+            "{} `{} {:>3.0}%`\n{} `{} {:>3.0}%`",
+            language.text("analysis-stay"),
+            bar(stay),
+            stay * 100.0,
+            language.text("analysis-switch"),
+            bar(1.0 - stay),
+            (1.0 - stay) * 100.0
+        )
+    }
+    pub fn generate_embed<'a>(
+        &self,
+        user: UserId,
+        language: Language,
+        embed: &'a mut CreateEmbed,
+    ) -> &'a mut CreateEmbed {
+        embed
+            .title(language.text("title"))
+            .description(language.format(
+                "analysis-of",
+                &[
+                    ("user", user.mention().to_string().into()),
+                    ("picks", self.total.into()),
+                ],
+            ));
+        if self.total == 0 {
+            return embed.field(
+                language.text("field-choices"),
+                language.text("analysis-no-picks"),
+                false,
+            );
+        }
+        let predictability = match self.predictability() {
+            Some(predictability) => {
+                format!("`{} {:>3.0}%`", bar(predictability), predictability * 100.0)
+            }
+            None => language.text("analysis-not-enough"),
+        };
+        embed
+            .field(
+                language.text("field-choices"),
+                self.distribution_chart(),
+                false,
+            )
+            .field(
+                language.text("field-transitions"),
+                self.transition_list(language),
+                false,
+            )
+            .field(
+                language.text("field-after-winning"),
+                Self::reaction(self.after_win, language),
+                true,
+            )
+            .field(
+                language.text("field-after-losing"),
+                Self::reaction(self.after_loss, language),
+                true,
+            )
+            .field(language.text("field-predictability"), predictability, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn picks(choices: impl IntoIterator<Item = Choice>) -> Vec<Pick> {
+        choices
+            .into_iter()
+            .enumerate()
+            .map(|(round, choice)| Pick {
+                game_id: "K7QX".to_string(),
+                round: round as u64 + 1,
+                choice,
+                outcome: Outcome::Draw,
+            })
+            .collect()
+    }
+
+    fn predictability(choices: impl IntoIterator<Item = Choice>) -> Option<f64> {
+        Analysis::new(&picks(choices)).predictability()
+    }
+
+    #[test]
+    fn random_picks_are_unpredictable() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let choices = (0..5000).map(|_| *Choice::all().choose(&mut rng).unwrap());
+        let predictability = predictability(choices).unwrap();
+        assert!(predictability < 0.02, "{}", predictability);
+    }
+
+    #[test]
+    fn constant_picks_are_predictable() {
+        assert_eq!(predictability([Choice::Rock; 20]), Some(1.0));
+    }
+
+    #[test]
+    fn cycle_is_predictable() {
+        let choices = Choice::all().into_iter().cycle().take(100).collect_vec();
+        // Every choice is picked equally often, only the order gives the cycle away.
+        assert!(entropy(Analysis::new(&picks(choices.clone())).distribution.values()) > 2.3);
+        let predictability = predictability(choices).unwrap();
+        assert!(predictability > 0.99, "{}", predictability);
+    }
+
+    #[test]
+    fn few_picks_are_not_rated() {
+        assert_eq!(predictability([Choice::Rock]), None);
+        assert_eq!(
+            predictability([Choice::Paper; MIN_PREDICTABILITY_PICKS - 1]),
+            None
+        );
+    }
+}
@@ -1,8 +0,0 @@
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub enum Choice {
-    Rock,
-    Paper,
-    Scissors,
-    Lizard,
-    Spock,
-}
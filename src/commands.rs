@@ -0,0 +1,336 @@
+use crate::guildconfig::{KEYS, MAX_ROUNDS};
+use crate::i18n::{localize_command, localize_option, Language};
+use rps_core::ruleset::RuleSet;
+use serde_json::{Map, Value};
+use serenity::builder::CreateApplicationCommands;
+use serenity::http::Http;
+use serenity::model::application::command::{Command, CommandOptionType};
+use serenity::model::id::GuildId;
+use serenity::model::permissions::Permissions;
+use std::collections::BTreeMap;
+use std::fmt;
+use tracing::warn;
+
+/// Where the slash commands are registered. Guild commands update instantly, which makes
+/// them useful for development, while global commands can take a while to propagate.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CommandScope {
+    Global,
+    Guild(GuildId),
+}
+
+impl fmt::Display for CommandScope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommandScope::Global => write!(f, "global"),
+            CommandScope::Guild(guild_id) => write!(f, "guild {}", guild_id),
+        }
+    }
+}
+
+/// Builds the complete set of slash commands the bot wants Discord to know about.
+pub fn create_commands(commands: &mut CreateApplicationCommands) -> &mut CreateApplicationCommands {
+    commands.create_application_command(|command| {
+        localize_command(command, "command-rockpaperscissors")
+            .create_option(|option| {
+                localize_option(option, "option-rounds")
+                    .kind(CommandOptionType::Integer)
+                    .min_int_value(1)
+                    .max_int_value(MAX_ROUNDS)
+            })
+            .create_option(|option| {
+                localize_option(option, "option-mode").kind(CommandOptionType::String);
+                RuleSet::all().into_iter().for_each(|rule_set| {
+                    option.add_string_choice_localized(
+                        Language::English.rule_set(rule_set),
+                        rule_set.name().to_string(),
+                        Language::all()
+                            .into_iter()
+                            .filter(|language| *language != Language::English)
+                            .map(|language| (language.locale(), language.rule_set(rule_set))),
+                    );
+                });
+                option
+            })
+    });
+    commands.create_application_command(|command| {
+        localize_command(command, "command-rps")
+            .create_option(|option| {
+                localize_option(option, "command-history")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-game-id")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|option| {
+                localize_option(option, "command-join")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-code")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|option| {
+                localize_option(option, "command-analyze")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-user").kind(CommandOptionType::User)
+                    })
+            })
+            .create_option(|option| {
+                localize_option(option, "command-export")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-game-id")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-format")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("JSON", "json")
+                            .add_string_choice("CSV", "csv")
+                    })
+            })
+            .create_option(|option| {
+                localize_option(option, "command-export-all")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-format")
+                            .kind(CommandOptionType::String)
+                            .add_string_choice("JSON", "json")
+                            .add_string_choice("CSV", "csv")
+                    })
+            })
+    });
+    commands.create_application_command(|command| localize_command(command, "command-rules"));
+    commands.create_application_command(|command| {
+        localize_command(command, "command-rps-config")
+            .default_member_permissions(Permissions::MANAGE_GUILD)
+            .dm_permission(false)
+            .create_option(|option| {
+                localize_option(option, "command-config-get").kind(CommandOptionType::SubCommand)
+            })
+            .create_option(|option| {
+                localize_option(option, "command-config-set")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-key")
+                            .kind(CommandOptionType::String)
+                            .required(true);
+                        KEYS.into_iter().for_each(|key| {
+                            option.add_string_choice(key, key);
+                        });
+                        option
+                    })
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-value")
+                            .kind(CommandOptionType::String)
+                            .required(true)
+                    })
+            })
+            .create_option(|option| {
+                localize_option(option, "command-config-reset")
+                    .kind(CommandOptionType::SubCommand)
+                    .create_sub_option(|option| {
+                        localize_option(option, "option-key").kind(CommandOptionType::String);
+                        KEYS.into_iter().for_each(|key| {
+                            option.add_string_choice(key, key);
+                        });
+                        option
+                    })
+            })
+    });
+    commands
+}
+
+/// The fields of a command that [`create_commands`] sets. Discord adds ids, versions and
+/// defaults to the commands it reports, which are left out when comparing.
+const COMMAND_FIELDS: [&str; 7] = [
+    "name",
+    "name_localizations",
+    "description",
+    "description_localizations",
+    "options",
+    "default_member_permissions",
+    "dm_permission",
+];
+
+/// Drops values that mean the same as leaving the field out: `null`, `false` and empty lists
+/// and objects.
+fn strip_defaults(value: Value) -> Option<Value> {
+    match value {
+        Value::Null | Value::Bool(false) => None,
+        Value::Array(items) if items.is_empty() => None,
+        Value::Array(items) => Some(Value::Array(
+            items
+                .into_iter()
+                .map(|item| strip_defaults(item).unwrap_or(Value::Null))
+                .collect(),
+        )),
+        Value::Object(fields) => {
+            let fields: Map<String, Value> = fields
+                .into_iter()
+                .filter_map(|(key, value)| Some((key, strip_defaults(value)?)))
+                .collect();
+            (!fields.is_empty()).then_some(Value::Object(fields))
+        }
+        value => Some(value),
+    }
+}
+
+/// Brings a command as built by [`create_commands`] or as reported by Discord into the same
+/// form, so the two can be compared.
+fn normalize_command(command: &Value, scope: CommandScope) -> Value {
+    let mut fields: Map<String, Value> = COMMAND_FIELDS
+        .into_iter()
+        .filter_map(|key| Some((key.to_string(), command.get(key)?.clone())))
+        .collect();
+    // The builder sends permissions as a string, serenity reports them as a number.
+    if let Some(Value::Number(permissions)) = fields.get("default_member_permissions") {
+        let permissions = Value::String(permissions.to_string());
+        fields.insert("default_member_permissions".to_string(), permissions);
+    }
+    // Guild commands cannot be used in DMs anyway, and Discord leaves the field out for
+    // them. A missing field means the command is allowed in DMs.
+    match scope {
+        CommandScope::Global => {
+            let allowed = fields.get("dm_permission") != Some(&Value::Bool(false));
+            fields.insert("dm_permission".to_string(), Value::Bool(allowed));
+        }
+        CommandScope::Guild(_) => {
+            fields.remove("dm_permission");
+        }
+    }
+    strip_defaults(Value::Object(fields)).unwrap_or(Value::Null)
+}
+
+/// The commands by name in the form [`normalize_command`] gives them.
+fn normalize_commands(commands: &[Value], scope: CommandScope) -> BTreeMap<String, Value> {
+    commands
+        .iter()
+        .filter_map(|command| {
+            let name = command.get("name")?.as_str()?.to_string();
+            Some((name, normalize_command(command, scope)))
+        })
+        .collect()
+}
+
+/// Brings the commands registered in `scope` in line with [`create_commands`].
+///
+/// The desired commands are compared against the commands Discord currently reports,
+/// including their options, descriptions and localizations. Only if anything differs are the
+/// commands bulk-overwritten, which also removes commands that are no longer defined.
+/// Returns whether the commands were overwritten.
+///
+/// In guild scope the global commands are left alone, as they may belong to another
+/// deployment of the same application. If there are any, the guild shows every command
+/// twice, so a warning is logged.
+pub async fn sync_commands(http: &Http, scope: CommandScope) -> serenity::Result<bool> {
+    let mut desired = CreateApplicationCommands::default();
+    create_commands(&mut desired);
+    let registered = match scope {
+        CommandScope::Global => {
+            Command::get_global_application_commands_with_localizations(http).await?
+        }
+        CommandScope::Guild(guild_id) => {
+            let global = Command::get_global_application_commands(http).await?;
+            if !global.is_empty() {
+                warn!(
+                    %scope,
+                    commands = global.len(),
+                    "Global commands are registered as well and show up twice in the guild"
+                );
+            }
+            guild_id
+                .get_application_commands_with_localizations(http)
+                .await?
+        }
+    };
+    let registered = registered
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<_>, _>>()?;
+    if normalize_commands(&registered, scope) == normalize_commands(&desired.0, scope) {
+        return Ok(false);
+    }
+    match scope {
+        CommandScope::Global => {
+            Command::set_global_application_commands(http, create_commands).await?;
+        }
+        CommandScope::Guild(guild_id) => {
+            guild_id
+                .set_application_commands(http, create_commands)
+                .await?;
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// The commands as Discord reports them after registering `desired`.
+    fn registered(desired: &[Value]) -> Vec<Value> {
+        desired
+            .iter()
+            .map(|command| {
+                let mut command = command.clone();
+                let fields = command.as_object_mut().unwrap();
+                fields.insert("id".to_string(), json!("1"));
+                fields.insert("application_id".to_string(), json!("2"));
+                fields.insert("version".to_string(), json!("3"));
+                fields.insert("type".to_string(), json!(1));
+                let command: Command = serde_json::from_value(command).unwrap();
+                serde_json::to_value(command).unwrap()
+            })
+            .collect()
+    }
+
+    fn desired() -> Vec<Value> {
+        let mut desired = CreateApplicationCommands::default();
+        create_commands(&mut desired);
+        desired.0
+    }
+
+    #[test]
+    fn registered_commands_match_desired() {
+        let desired = desired();
+        for scope in [CommandScope::Global, CommandScope::Guild(GuildId(1))] {
+            assert_eq!(
+                normalize_commands(&registered(&desired), scope),
+                normalize_commands(&desired, scope)
+            );
+        }
+    }
+
+    #[test]
+    fn changed_option_is_detected() {
+        let desired = desired();
+        let expected = normalize_commands(&desired, CommandScope::Global);
+        let mut commands = registered(&desired);
+        commands[0]["options"][0]["max_value"] = json!(5);
+        assert_ne!(
+            normalize_commands(&commands, CommandScope::Global),
+            expected
+        );
+        let mut commands = registered(&desired);
+        commands[1]["options"][0]["description_localizations"]["de"] = json!("Alt");
+        assert_ne!(
+            normalize_commands(&commands, CommandScope::Global),
+            expected
+        );
+        // The config command is not allowed in DMs.
+        let mut commands = registered(&desired);
+        commands[3]["dm_permission"] = json!(true);
+        assert_ne!(
+            normalize_commands(&commands, CommandScope::Global),
+            expected
+        );
+    }
+}
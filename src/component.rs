@@ -0,0 +1,224 @@
+use rps_core::choice::Choice;
+use std::fmt;
+
+/// The version of the custom id format written by [`ComponentId::encode`]. Ids of any other
+/// version are rejected, so buttons of messages sent before a format change stop working
+/// with a clear message instead of doing something unexpected.
+pub const VERSION: &str = "v1";
+
+/// What a button of a game message does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComponentAction {
+    Join,
+    Leave,
+    Start,
+    Choose(Choice),
+    ShowHistory,
+    HistoryPage(usize),
+    ShowResults,
+    ResultsPage(usize),
+}
+
+impl ComponentAction {
+    fn tag(&self) -> &'static str {
+        match self {
+            ComponentAction::Join => "join",
+            ComponentAction::Leave => "leave",
+            ComponentAction::Start => "start",
+            ComponentAction::Choose(_) => "choose",
+            ComponentAction::ShowHistory => "history",
+            ComponentAction::HistoryPage(_) => "historypage",
+            ComponentAction::ShowResults => "results",
+            ComponentAction::ResultsPage(_) => "resultspage",
+        }
+    }
+    fn argument(&self) -> Option<String> {
+        match self {
+            ComponentAction::Choose(choice) => Some(choice.to_char().to_string()),
+            ComponentAction::HistoryPage(page) | ComponentAction::ResultsPage(page) => {
+                Some(page.to_string())
+            }
+            _ => None,
+        }
+    }
+    fn parse(tag: &str, argument: Option<&str>) -> Option<Self> {
+        let page = || argument?.parse().ok();
+        Some(match (tag, argument) {
+            ("join", None) => ComponentAction::Join,
+            ("leave", None) => ComponentAction::Leave,
+            ("start", None) => ComponentAction::Start,
+            ("choose", Some(choice)) => {
+                let mut chars = choice.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => ComponentAction::Choose(Choice::from_char(c)?),
+                    _ => return None,
+                }
+            }
+            ("history", None) => ComponentAction::ShowHistory,
+            ("historypage", Some(_)) => ComponentAction::HistoryPage(page()?),
+            ("results", None) => ComponentAction::ShowResults,
+            ("resultspage", Some(_)) => ComponentAction::ResultsPage(page()?),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ComponentError {
+    /// The id was written by another version of the bot.
+    UnknownVersion,
+    /// The id is not in the expected format or names an unknown action.
+    Malformed,
+}
+
+impl fmt::Display for ComponentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComponentError::UnknownVersion => write!(f, "unknown custom id version"),
+            ComponentError::Malformed => write!(f, "malformed custom id"),
+        }
+    }
+}
+
+/// The custom id of a button: the action, the game it belongs to and the nonce of that game.
+/// A button only works if its nonce matches the game's, which rejects ids made up for a
+/// game by someone who only knows its id.
+///
+/// Encoded as `v1:<action>:<game id>:<nonce>[:<argument>]`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ComponentId {
+    pub action: ComponentAction,
+    pub game_id: String,
+    pub nonce: u32,
+}
+
+impl ComponentId {
+    pub fn new(action: ComponentAction, game_id: &str, nonce: u32) -> Self {
+        ComponentId {
+            action,
+            game_id: game_id.to_string(),
+            nonce,
+        }
+    }
+    pub fn encode(&self) -> String {
+        let mut id = format!(
+            "{}:{}:{}:{:08x}",
+            VERSION,
+            self.action.tag(),
+            self.game_id,
+            self.nonce
+        );
+        if let Some(argument) = self.action.argument() {
+            id.push(':');
+            id.push_str(&argument);
+        }
+        id
+    }
+    pub fn decode(id: &str) -> Result<Self, ComponentError> {
+        let mut parts = id.split(':');
+        if parts.next() != Some(VERSION) {
+            return Err(ComponentError::UnknownVersion);
+        }
+        let (tag, game_id, nonce) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(tag), Some(game_id), Some(nonce)) if !game_id.is_empty() => (tag, game_id, nonce),
+            _ => return Err(ComponentError::Malformed),
+        };
+        let argument = parts.next();
+        if parts.next().is_some() {
+            return Err(ComponentError::Malformed);
+        }
+        Ok(ComponentId {
+            action: ComponentAction::parse(tag, argument).ok_or(ComponentError::Malformed)?,
+            game_id: game_id.to_string(),
+            nonce: match nonce.len() == 8 && nonce.chars().all(|c| c.is_ascii_hexdigit()) {
+                true => u32::from_str_radix(nonce, 16).map_err(|_| ComponentError::Malformed)?,
+                false => return Err(ComponentError::Malformed),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_actions() -> Vec<ComponentAction> {
+        let mut actions = vec![
+            ComponentAction::Join,
+            ComponentAction::Leave,
+            ComponentAction::Start,
+            ComponentAction::ShowHistory,
+            ComponentAction::HistoryPage(0),
+            ComponentAction::HistoryPage(12),
+            ComponentAction::ShowResults,
+            ComponentAction::ResultsPage(0),
+            ComponentAction::ResultsPage(7),
+        ];
+        actions.extend(Choice::all().into_iter().map(ComponentAction::Choose));
+        actions
+    }
+
+    #[test]
+    fn every_action_round_trips() {
+        for action in all_actions() {
+            for nonce in [0, 0xdeadbeef, u32::MAX] {
+                let id = ComponentId::new(action, "K7QX", nonce);
+                assert_eq!(ComponentId::decode(&id.encode()), Ok(id));
+            }
+        }
+    }
+
+    #[test]
+    fn encoded_format() {
+        assert_eq!(
+            ComponentId::new(ComponentAction::Choose(Choice::Rock), "K7QX", 0xab).encode(),
+            format!("v1:choose:K7QX:000000ab:{}", Choice::Rock.to_char())
+        );
+        assert_eq!(
+            ComponentId::new(ComponentAction::HistoryPage(3), "K7QX", 1).encode(),
+            "v1:historypage:K7QX:00000001:3"
+        );
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        for id in ["v2:join:K7QX:00000001", "join:K7QX", "rps_join_123", ""] {
+            assert_eq!(ComponentId::decode(id), Err(ComponentError::UnknownVersion));
+        }
+    }
+
+    #[test]
+    fn malformed_ids_are_rejected() {
+        for id in [
+            // extra segments
+            "v1:join:K7QX:00000001:x",
+            "v1:historypage:K7QX:00000001:1:2",
+            // missing parts
+            "v1:join:K7QX",
+            "v1:join::00000001",
+            "v1:choose:K7QX:00000001",
+            // bad nonces
+            "v1:join:K7QX:zzzzzzzz",
+            "v1:join:K7QX:1",
+            "v1:join:K7QX:+0000001",
+            "v1:join:K7QX:1ffffffff",
+            // multi-char and unknown choices
+            "v1:choose:K7QX:00000001:rp",
+            "v1:choose:K7QX:00000001:",
+            "v1:choose:K7QX:00000001:?",
+            // non-numeric and negative pages
+            "v1:historypage:K7QX:00000001:one",
+            "v1:resultspage:K7QX:00000001:-1",
+            "v1:resultspage:K7QX:00000001",
+            // unknown action
+            "v1:cheat:K7QX:00000001",
+        ] {
+            assert_eq!(
+                ComponentId::decode(id),
+                Err(ComponentError::Malformed),
+                "{}",
+                id
+            );
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use serde::Deserialize;
+use serenity::model::id::GuildId;
+use serenity::prelude::GatewayIntents;
+use std::fmt;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+pub const DEFAULT_CONFIG_PATH: &str = "config.toml";
+pub const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+pub const LOG_FORMATS: [&str; 2] = ["text", "json"];
+pub const MIN_ADMIN_TOKEN_LENGTH: usize = 16;
+const INTENTS: [(&str, GatewayIntents); 8] = [
+    ("guilds", GatewayIntents::GUILDS),
+    ("guild-members", GatewayIntents::GUILD_MEMBERS),
+    ("guild-messages", GatewayIntents::GUILD_MESSAGES),
+    (
+        "guild-message-reactions",
+        GatewayIntents::GUILD_MESSAGE_REACTIONS,
+    ),
+    ("direct-messages", GatewayIntents::DIRECT_MESSAGES),
+    (
+        "direct-message-reactions",
+        GatewayIntents::DIRECT_MESSAGE_REACTIONS,
+    ),
+    ("message-content", GatewayIntents::MESSAGE_CONTENT),
+    ("guild-presences", GatewayIntents::GUILD_PRESENCES),
+];
+
+/// The raw contents of the configuration file. Every field is optional so a deployment
+/// can provide everything through the environment instead.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct ConfigFile {
+    token: Option<String>,
+    intents: Option<Vec<String>>,
+    storage_path: Option<PathBuf>,
+    log_level: Option<String>,
+    log_format: Option<String>,
+    dev_guild_id: Option<u64>,
+    shard_count: Option<u64>,
+    http_address: Option<String>,
+    admin_token: Option<String>,
+}
+
+/// Validated process configuration, loaded from a TOML file and overridden by the
+/// `DISCORD_TOKEN`, `DISCORD_INTENTS`, `STORAGE_PATH`, `LOG_LEVEL`, `LOG_FORMAT`,
+/// `DEV_GUILD_ID`, `SHARD_COUNT`, `HTTP_ADDRESS` and `ADMIN_TOKEN` environment variables.
+#[derive(Clone)]
+pub struct Config {
+    pub token: String,
+    pub intents: GatewayIntents,
+    pub storage_path: PathBuf,
+    pub log_level: String,
+    pub log_format: String,
+    /// Registers the commands in this guild only. Global commands that are still registered
+    /// are not removed and show up next to them.
+    pub dev_guild_id: Option<GuildId>,
+    pub shard_count: Option<u64>,
+    pub http_address: Option<SocketAddr>,
+    pub admin_token: Option<String>,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn parse_number(name: &str, value: &str, errors: &mut Vec<String>) -> Option<u64> {
+    match value.trim().parse() {
+        Ok(number) => Some(number),
+        Err(_) => {
+            errors.push(format!(
+                "{} must be a positive number, got {:?}",
+                name, value
+            ));
+            None
+        }
+    }
+}
+
+fn parse_intents(names: &[String], errors: &mut Vec<String>) -> GatewayIntents {
+    names
+        .iter()
+        .map(|name| name.trim().to_lowercase().replace('_', "-"))
+        .filter(|name| !name.is_empty())
+        .fold(GatewayIntents::empty(), |intents, name| {
+            match INTENTS.iter().find(|(intent, _)| *intent == name) {
+                Some((_, intent)) => intents.union(*intent),
+                None => {
+                    errors.push(format!(
+                        "unknown intent {:?}, expected one of {}",
+                        name,
+                        INTENTS.map(|(intent, _)| intent).join(", ")
+                    ));
+                    intents
+                }
+            }
+        })
+}
+
+fn check_token(token: &str) -> Result<(), String> {
+    let token = token.trim_start_matches("Bot ");
+    if token.split('.').count() != 3 || token.chars().any(char::is_whitespace) {
+        return Err("token does not look like a Discord bot token".to_string());
+    }
+    Ok(())
+}
+
+impl Config {
+    /// Loads the configuration from `path`. A missing file is only an error if the path
+    /// was given explicitly, otherwise the environment alone is used.
+    pub fn load(path: Option<PathBuf>) -> Result<Config, Vec<String>> {
+        let mut errors = vec![];
+        let explicit = path.is_some() || env_var("CONFIG_PATH").is_some();
+        let path = path
+            .or_else(|| env_var("CONFIG_PATH").map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+        let file = match fs::read_to_string(&path) {
+            Ok(data) => toml::from_str(&data).unwrap_or_else(|why| {
+                errors.push(format!("cannot parse {}: {}", path.display(), why));
+                ConfigFile::default()
+            }),
+            Err(why) if explicit => {
+                errors.push(format!("cannot read {}: {}", path.display(), why));
+                ConfigFile::default()
+            }
+            Err(_) => ConfigFile::default(),
+        };
+
+        let token = env_var("DISCORD_TOKEN").or(file.token);
+        match &token {
+            Some(token) => {
+                if let Err(why) = check_token(token) {
+                    errors.push(why);
+                }
+            }
+            None => errors
+                .push("no bot token, set DISCORD_TOKEN or `token` in the config file".to_string()),
+        }
+        let intents = match env_var("DISCORD_INTENTS") {
+            Some(intents) => Some(intents.split(',').map(str::to_string).collect()),
+            None => file.intents,
+        }
+        .map(|names: Vec<String>| parse_intents(&names, &mut errors))
+        .unwrap_or(GatewayIntents::GUILD_MESSAGE_REACTIONS.union(GatewayIntents::GUILD_MESSAGES));
+        let storage_path = env_var("STORAGE_PATH")
+            .map(PathBuf::from)
+            .or(file.storage_path)
+            .unwrap_or_else(|| PathBuf::from("data"));
+        if storage_path.is_file() {
+            errors.push(format!(
+                "storage path {} is a file, not a directory",
+                storage_path.display()
+            ));
+        }
+        let log_level = env_var("LOG_LEVEL")
+            .or(file.log_level)
+            .unwrap_or_else(|| "info".to_string())
+            .to_lowercase();
+        if !LOG_LEVELS.contains(&log_level.as_str()) {
+            errors.push(format!(
+                "unknown log level {:?}, expected one of {}",
+                log_level,
+                LOG_LEVELS.join(", ")
+            ));
+        }
+        let log_format = env_var("LOG_FORMAT")
+            .or(file.log_format)
+            .unwrap_or_else(|| "text".to_string())
+            .to_lowercase();
+        if !LOG_FORMATS.contains(&log_format.as_str()) {
+            errors.push(format!(
+                "unknown log format {:?}, expected one of {}",
+                log_format,
+                LOG_FORMATS.join(", ")
+            ));
+        }
+        let dev_guild_id = match env_var("DEV_GUILD_ID") {
+            Some(id) => parse_number("DEV_GUILD_ID", &id, &mut errors),
+            None => file.dev_guild_id,
+        };
+        let shard_count = match env_var("SHARD_COUNT") {
+            Some(count) => parse_number("SHARD_COUNT", &count, &mut errors),
+            None => file.shard_count,
+        };
+        if shard_count == Some(0) {
+            errors.push("shard count must be at least 1".to_string());
+        }
+        let http_address = env_var("HTTP_ADDRESS")
+            .or(file.http_address)
+            .and_then(|address| match address.trim().parse() {
+                Ok(address) => Some(address),
+                Err(_) => {
+                    errors.push(format!(
+                        "HTTP address must look like 127.0.0.1:9000, got {:?}",
+                        address
+                    ));
+                    None
+                }
+            });
+
+        let admin_token = env_var("ADMIN_TOKEN").or(file.admin_token);
+        if admin_token
+            .as_ref()
+            .is_some_and(|token| token.len() < MIN_ADMIN_TOKEN_LENGTH)
+        {
+            errors.push(format!(
+                "admin token must be at least {} characters long",
+                MIN_ADMIN_TOKEN_LENGTH
+            ));
+        }
+
+        match token {
+            Some(token) if errors.is_empty() => Ok(Config {
+                token,
+                intents,
+                storage_path,
+                log_level,
+                log_format,
+                dev_guild_id: dev_guild_id.map(GuildId),
+                shard_count,
+                http_address,
+                admin_token,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "token: <{} characters>", self.token.len())?;
+        writeln!(
+            f,
+            "intents: {}",
+            INTENTS
+                .iter()
+                .filter(|(_, intent)| self.intents.contains(*intent))
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )?;
+        writeln!(f, "storage path: {}", self.storage_path.display())?;
+        writeln!(f, "log level: {}", self.log_level)?;
+        writeln!(f, "log format: {}", self.log_format)?;
+        match self.dev_guild_id {
+            Some(guild_id) => writeln!(f, "dev guild: {}", guild_id)?,
+            None => writeln!(f, "dev guild: none, commands are registered globally")?,
+        }
+        match self.shard_count {
+            Some(shard_count) => writeln!(f, "shards: {}", shard_count)?,
+            None => writeln!(f, "shards: 1")?,
+        }
+        match self.http_address {
+            Some(address) => writeln!(f, "http server: {}", address)?,
+            None => writeln!(f, "http server: disabled")?,
+        }
+        match self.admin_token {
+            Some(_) => write!(f, "admin api: enabled"),
+            None => write!(f, "admin api: disabled"),
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use crate::component::{ComponentAction, ComponentId};
+use crate::helper::{
+    fit_lines, round_choice_list, round_point_list, round_result_list, FIELD_LIMIT,
+};
+use crate::i18n::Language;
+use itertools::Itertools;
+use rand::random;
+use rps_core::game::Game;
+use rps_core::outcome::{CyclicPolicy, RoundOutcome};
+use rps_core::ruleset::RuleSet;
+use rps_core::state::{GameError, GameState};
+use serenity::builder::CreateEmbed;
+use serenity::model::id::{ChannelId, GuildId, MessageId};
+use serenity::model::user::User;
+use serenity::utils::MessageBuilder;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How many players the points list of the game message shows.
+const POINT_LIST_SIZE: usize = 10;
+/// How many players one page of the full results shows.
+const STANDINGS_PAGE_SIZE: usize = 20;
+
+pub struct DiscordGame {
+    game: Game<User>,
+    guild_id: Option<GuildId>,
+    language: Language,
+    round_timeout: Option<u64>,
+    round_started: SystemTime,
+    message: Option<(ChannelId, MessageId)>,
+    nonce: u32,
+}
+
+impl Deref for DiscordGame {
+    type Target = Game<User>;
+    fn deref(&self) -> &Self::Target {
+        &self.game
+    }
+}
+
+impl DerefMut for DiscordGame {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.game
+    }
+}
+
+impl DiscordGame {
+    pub fn new(
+        id: String,
+        guild_id: Option<GuildId>,
+        language: Language,
+        rule_set: RuleSet,
+        rounds: u64,
+        round_timeout: Option<u64>,
+        cyclic_policy: CyclicPolicy,
+    ) -> Self {
+        let mut game = Game::new(id, rule_set, rounds);
+        game.set_cyclic_policy(cyclic_policy);
+        DiscordGame {
+            game,
+            guild_id,
+            language,
+            round_timeout,
+            round_started: SystemTime::now(),
+            message: None,
+            nonce: random(),
+        }
+    }
+    /// Wraps a game that was rebuilt from storage. It has no message and no deadline, as
+    /// only finished games are restored.
+    pub fn restore(
+        game: Game<User>,
+        guild_id: Option<GuildId>,
+        language: Language,
+        nonce: u32,
+    ) -> Self {
+        DiscordGame {
+            game,
+            guild_id,
+            language,
+            round_timeout: None,
+            round_started: SystemTime::now(),
+            message: None,
+            nonce,
+        }
+    }
+    pub fn start(&mut self) -> Result<(), GameError> {
+        self.game.start()?;
+        self.round_started = SystemTime::now();
+        Ok(())
+    }
+    pub fn resolve_round(&mut self) -> Result<(), GameError> {
+        self.game.resolve_round()?;
+        self.round_started = SystemTime::now();
+        Ok(())
+    }
+    pub fn get_deadline(&self) -> Option<SystemTime> {
+        Some(self.round_started + Duration::from_secs(self.round_timeout?))
+    }
+    pub fn get_guild_id(&self) -> Option<GuildId> {
+        self.guild_id
+    }
+    pub fn get_language(&self) -> Language {
+        self.language
+    }
+    pub fn get_nonce(&self) -> u32 {
+        self.nonce
+    }
+    pub fn get_message(&self) -> Option<(ChannelId, MessageId)> {
+        self.message
+    }
+    pub fn set_message(&mut self, channel_id: ChannelId, message_id: MessageId) {
+        self.message = Some((channel_id, message_id));
+    }
+    /// Whether a button with this id was created for this game.
+    pub fn accepts(&self, component_id: &ComponentId) -> bool {
+        component_id.game_id == *self.get_id() && component_id.nonce == self.nonce
+    }
+    /// The custom id of a button of this game that triggers `action`.
+    pub fn custom_id(&self, action: ComponentAction) -> String {
+        ComponentId::new(action, self.get_id(), self.nonce).encode()
+    }
+}
+
+impl DiscordGame {
+    /// Every player with their points, best first. Players with the same points share a
+    /// rank.
+    fn get_standings(&self) -> Vec<(usize, &User, u64)> {
+        let standings = self
+            .get_points()
+            .iter()
+            .sorted_by_key(|(user, points)| (std::cmp::Reverse(**points), user.id))
+            .collect_vec();
+        let mut rank = 0;
+        standings
+            .iter()
+            .enumerate()
+            .map(|(index, (user, points))| {
+                if index == 0 || standings[index - 1].1 != *points {
+                    rank = index + 1;
+                }
+                (rank, *user, **points)
+            })
+            .collect()
+    }
+    fn standing_line(&self, rank: usize, user: &User, points: u64) -> String {
+        MessageBuilder::new()
+            .push(format!("#{} ", rank))
+            .mention(user)
+            .push(" ")
+            .push(self.language.format("points", &[("points", points.into())]))
+            .push("\n")
+            .build()
+    }
+    fn generate_point_list(&self) -> String {
+        let standings = self.get_standings();
+        let mut list: String = standings
+            .iter()
+            .take(POINT_LIST_SIZE)
+            .map(|(rank, user, points)| self.standing_line(*rank, user, *points))
+            .collect();
+        if standings.len() > POINT_LIST_SIZE {
+            list.push_str(&self.language.format(
+                "and-more",
+                &[("count", (standings.len() - POINT_LIST_SIZE).into())],
+            ));
+        }
+        list
+    }
+    pub fn get_standings_pages(&self) -> usize {
+        self.get_player_count().div_ceil(STANDINGS_PAGE_SIZE).max(1)
+    }
+    /// Shows one page of every player's points, together with the rank of `viewer`.
+    pub fn generate_standings_embed<'a>(
+        &self,
+        embed: &'a mut CreateEmbed,
+        page: usize,
+        viewer: &User,
+        language: Language,
+    ) -> &'a mut CreateEmbed {
+        let standings = self.get_standings();
+        embed.title(language.text("title")).footer(|footer| {
+            footer.text(language.format(
+                "standings-footer",
+                &[
+                    ("id", self.get_id().into()),
+                    ("page", (page + 1).into()),
+                    ("pages", self.get_standings_pages().into()),
+                ],
+            ))
+        });
+        if let Some((rank, _, points)) = standings.iter().find(|(_, user, _)| *user == viewer) {
+            embed.description(language.format(
+                "standings-own-rank",
+                &[
+                    ("rank", (*rank).into()),
+                    ("players", standings.len().into()),
+                    ("points", (*points).into()),
+                ],
+            ));
+        }
+        embed.field(
+            language.text("field-points"),
+            fit_lines(
+                standings
+                    .iter()
+                    .skip(page * STANDINGS_PAGE_SIZE)
+                    .take(STANDINGS_PAGE_SIZE)
+                    .map(|(rank, user, points)| self.standing_line(*rank, user, *points)),
+                FIELD_LIMIT,
+                language,
+            ),
+            false,
+        )
+    }
+    pub fn generate_embed<'a>(&self, embed: &'a mut CreateEmbed) -> &'a mut CreateEmbed {
+        let language = self.language;
+        embed.footer(|footer| {
+            footer.text(language.format("game-footer", &[("id", self.get_id().into())]))
+        });
+        if self.get_state() == GameState::Lobby {
+            embed.title(language.text("title")).description(format!(
+                "{}\n{}\n{}",
+                language.format("lobby-rounds", &[("rounds", self.get_rounds().into())]),
+                language.rule_set(self.get_rule_set()),
+                language.format("lobby-code", &[("code", self.get_id().into())])
+            ));
+            let header = language.text("lobby-players") + "\n";
+            let players = fit_lines(
+                self.get_players()
+                    .iter()
+                    .sorted_by_key(|user| user.id)
+                    .map(|user| MessageBuilder::new().mention(user).push("\n").build()),
+                FIELD_LIMIT - header.chars().count(),
+                language,
+            );
+            return embed.field(language.text("field-players"), header + &players, false);
+        }
+        match self.get_history().last().filter(|_| {
+            matches!(
+                self.get_state(),
+                GameState::Revealing | GameState::Finished | GameState::Cancelled
+            )
+        }) {
+            Some(round) => {
+                let msg = match round.outcome {
+                    RoundOutcome::Unanimous(choice) => MessageBuilder::new()
+                        .push(language.format("all-chose", &[("choice", choice.emoji().into())]))
+                        .push("\n")
+                        .push(language.text("no-one-wins"))
+                        .build(),
+                    RoundOutcome::CyclicDraw => MessageBuilder::new()
+                        .push(language.text("cyclic-draw"))
+                        .push("\n")
+                        .push(match self.get_cyclic_policy() {
+                            CyclicPolicy::Replay => language.text("round-replayed"),
+                            _ => round_result_list(round, language),
+                        })
+                        .build(),
+                    RoundOutcome::Normal => round_result_list(round, language),
+                };
+                embed.field(language.text("field-choices"), msg, false);
+            }
+            None => {
+                let mut msg = MessageBuilder::new();
+                if self.get_rounds() > 1 {
+                    msg.push(language.format(
+                        "round-counter",
+                        &[
+                            ("round", self.get_round().into()),
+                            ("rounds", self.get_rounds().into()),
+                        ],
+                    ))
+                    .push("\n");
+                }
+                msg.push(language.text("choose-weapon"))
+                    .push("\n")
+                    .push(language.format(
+                        "players-chose",
+                        &[
+                            ("chosen", self.get_finished_players().into()),
+                            ("players", self.get_player_count().into()),
+                        ],
+                    ));
+                if let Some(deadline) = self.get_deadline() {
+                    let timestamp = deadline
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    msg.push("\n").push(language.format(
+                        "round-deadline",
+                        &[("deadline", format!("<t:{}:R>", timestamp).into())],
+                    ));
+                }
+                embed.description(msg.build());
+            }
+        }
+        embed.field(
+            language.text("field-points"),
+            self.generate_point_list(),
+            false,
+        );
+        embed.title(language.text("title"))
+    }
+    pub fn generate_history_embed<'a>(
+        &self,
+        embed: &'a mut CreateEmbed,
+        page: usize,
+        language: Language,
+    ) -> &'a mut CreateEmbed {
+        embed.title(language.text("title")).footer(|footer| {
+            footer.text(language.format(
+                "history-footer",
+                &[
+                    ("id", self.get_id().into()),
+                    ("page", (page + 1).into()),
+                    ("pages", self.get_history().len().max(1).into()),
+                ],
+            ))
+        });
+        match self.get_history().get(page) {
+            Some(round) => embed
+                .description(language.format(
+                    "history-round",
+                    &[
+                        ("round", round.number.into()),
+                        ("rounds", self.get_rounds().into()),
+                    ],
+                ))
+                .field(
+                    language.text("field-choices"),
+                    round_choice_list(round, language),
+                    false,
+                )
+                .field(
+                    language.text("field-results"),
+                    round_result_list(round, language),
+                    false,
+                )
+                .field(
+                    language.text("field-points"),
+                    round_point_list(round, language),
+                    false,
+                ),
+            None => embed.description(language.text("history-empty")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: &str) -> DiscordGame {
+        DiscordGame::new(
+            id.to_string(),
+            None,
+            Language::English,
+            RuleSet::Classic,
+            1,
+            None,
+            CyclicPolicy::default(),
+        )
+    }
+
+    #[test]
+    fn accepts_only_its_own_buttons() {
+        let game = game("K7QX");
+        let own = ComponentId::decode(&game.custom_id(ComponentAction::Join)).unwrap();
+        assert!(game.accepts(&own));
+        let stale = ComponentId::new(ComponentAction::Join, "K7QX", game.get_nonce() ^ 1);
+        assert!(!game.accepts(&stale));
+        let other = ComponentId::new(ComponentAction::Join, "ABCD", game.get_nonce());
+        assert!(!game.accepts(&other));
+    }
+}
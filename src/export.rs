@@ -0,0 +1,369 @@
+use crate::discordgame::DiscordGame;
+use crate::i18n::Language;
+use itertools::Itertools;
+use rand::random;
+use rps_core::choice::Choice;
+use rps_core::choiceresult::ChoiceResult;
+use rps_core::event::LoggedEvent;
+use rps_core::game::Game;
+use rps_core::outcome::CyclicPolicy;
+use rps_core::round::Round;
+use rps_core::ruleset::RuleSet;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+use serenity::model::user::User;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "csv" => ExportFormat::Csv,
+            _ => ExportFormat::Json,
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerExport {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerChoiceExport {
+    pub player: u64,
+    pub choice: Choice,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerPointsExport {
+    pub player: u64,
+    pub points: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ChoiceResultExport {
+    pub winner_choice: Choice,
+    #[serde(default)]
+    pub winners: usize,
+    pub loser_choice: Choice,
+    #[serde(default)]
+    pub losers: usize,
+    pub verb: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RoundExport {
+    pub number: u64,
+    pub choices: Vec<PlayerChoiceExport>,
+    pub results: Vec<ChoiceResultExport>,
+    pub points: Vec<PlayerPointsExport>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameExport {
+    pub id: String,
+    pub guild_id: Option<u64>,
+    pub rule_set: String,
+    pub rounds: u64,
+    #[serde(default)]
+    pub cyclic_policy: CyclicPolicy,
+    pub players: Vec<PlayerExport>,
+    pub history: Vec<RoundExport>,
+    pub points: Vec<PlayerPointsExport>,
+    /// Every change to the game, enough to replay it with [`rps_core::game::Game::from_events`].
+    #[serde(default)]
+    pub events: Vec<LoggedEvent<u64>>,
+    #[serde(default)]
+    pub language: Option<String>,
+    /// The nonce of the game's buttons. It is only kept in storage, so History buttons keep
+    /// working once the game is rebuilt, and left out of downloads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    game_id: &'a str,
+    guild_id: Option<u64>,
+    rule_set: &'a str,
+    round: u64,
+    player_id: u64,
+    player_name: &'a str,
+    choice: Choice,
+    wins: usize,
+    losses: usize,
+    round_points: u64,
+    total_points: u64,
+}
+
+fn export_points(points: &HashMap<User, u64>) -> Vec<PlayerPointsExport> {
+    points
+        .iter()
+        .map(|(user, points)| PlayerPointsExport {
+            player: user.id.0,
+            points: *points,
+        })
+        .sorted_by_key(|points| (std::cmp::Reverse(points.points), points.player))
+        .collect()
+}
+
+impl From<&ChoiceResult> for ChoiceResultExport {
+    fn from(result: &ChoiceResult) -> Self {
+        ChoiceResultExport {
+            winner_choice: result.winner_choice,
+            winners: result.winners,
+            loser_choice: result.loser_choice,
+            losers: result.losers,
+            verb: result.verb.clone(),
+        }
+    }
+}
+
+impl From<&Round<User>> for RoundExport {
+    fn from(round: &Round<User>) -> Self {
+        RoundExport {
+            number: round.number,
+            choices: round
+                .choices
+                .iter()
+                .map(|(user, choice)| PlayerChoiceExport {
+                    player: user.id.0,
+                    choice: *choice,
+                })
+                .sorted_by_key(|choice| choice.player)
+                .collect(),
+            results: round.results.iter().map(ChoiceResultExport::from).collect(),
+            points: export_points(&round.points),
+        }
+    }
+}
+
+impl From<&DiscordGame> for GameExport {
+    fn from(game: &DiscordGame) -> Self {
+        GameExport {
+            id: game.get_id().clone(),
+            guild_id: game.get_guild_id().map(|guild_id| guild_id.0),
+            rule_set: game.get_rule_set().name().to_string(),
+            rounds: game.get_rounds(),
+            cyclic_policy: game.get_cyclic_policy(),
+            players: game
+                .get_players()
+                .iter()
+                .map(|user| PlayerExport {
+                    id: user.id.0,
+                    name: user.tag(),
+                })
+                .sorted_by_key(|player| player.id)
+                .collect(),
+            history: game.get_history().iter().map(RoundExport::from).collect(),
+            points: export_points(game.get_points()),
+            events: game
+                .get_events()
+                .iter()
+                .map(|event| event.map_player(|user| user.id.0))
+                .collect(),
+            language: Some(game.get_language().locale().to_string()),
+            nonce: Some(game.get_nonce()),
+        }
+    }
+}
+
+impl PlayerExport {
+    fn to_user(&self) -> User {
+        let (name, discriminator) = match self.name.rsplit_once('#') {
+            Some((name, discriminator)) => (name, discriminator.parse().unwrap_or_default()),
+            None => (self.name.as_str(), 0),
+        };
+        let mut user = User::default();
+        user.id = UserId(self.id);
+        user.name = name.to_string();
+        user.discriminator = discriminator;
+        user.avatar = None;
+        user.bot = false;
+        user
+    }
+}
+
+impl GameExport {
+    /// Rebuilds a stored game by replaying its events. Returns `None` for games stored
+    /// before events were recorded, or whose events do not make a valid game.
+    pub fn to_game(&self) -> Option<DiscordGame> {
+        if self.events.is_empty() {
+            return None;
+        }
+        let players: HashMap<u64, User> = self
+            .players
+            .iter()
+            .map(|player| (player.id, player.to_user()))
+            .collect();
+        let events = self.events.iter().map(|event| {
+            event.map_player(|id| {
+                players.get(id).cloned().unwrap_or_else(|| {
+                    PlayerExport {
+                        id: *id,
+                        name: String::new(),
+                    }
+                    .to_user()
+                })
+            })
+        });
+        let game = Game::from_events(
+            self.id.clone(),
+            RuleSet::from_name(&self.rule_set)?,
+            self.rounds,
+            self.cyclic_policy,
+            events,
+        )
+        .ok()?;
+        Some(DiscordGame::restore(
+            game,
+            self.guild_id.map(GuildId),
+            Language::from_locale(self.language.as_deref().unwrap_or_default()),
+            self.nonce.unwrap_or_else(random),
+        ))
+    }
+    fn player_name(&self, id: u64) -> &str {
+        self.players
+            .iter()
+            .find(|player| player.id == id)
+            .map(|player| player.name.as_str())
+            .unwrap_or_default()
+    }
+    fn write_csv_rows(&self, writer: &mut csv::Writer<Vec<u8>>) -> csv::Result<()> {
+        for round in &self.history {
+            for choice in &round.choices {
+                writer.serialize(CsvRow {
+                    game_id: &self.id,
+                    guild_id: self.guild_id,
+                    rule_set: &self.rule_set,
+                    round: round.number,
+                    player_id: choice.player,
+                    player_name: self.player_name(choice.player),
+                    choice: choice.choice,
+                    wins: round
+                        .results
+                        .iter()
+                        .filter(|result| result.winner_choice == choice.choice)
+                        .map(|result| result.losers)
+                        .sum(),
+                    losses: round
+                        .results
+                        .iter()
+                        .filter(|result| result.loser_choice == choice.choice)
+                        .map(|result| result.winners)
+                        .sum(),
+                    round_points: round
+                        .points
+                        .iter()
+                        .find(|points| points.player == choice.player)
+                        .map(|points| points.points)
+                        .unwrap_or(0),
+                    total_points: self
+                        .points
+                        .iter()
+                        .find(|points| points.player == choice.player)
+                        .map(|points| points.points)
+                        .unwrap_or(0),
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn export_games(games: &[GameExport], format: ExportFormat) -> Result<Vec<u8>, String> {
+    match format {
+        ExportFormat::Json => match games {
+            [game] => serde_json::to_vec_pretty(game),
+            games => serde_json::to_vec_pretty(games),
+        }
+        .map_err(|why| why.to_string()),
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for game in games {
+                game.write_csv_rows(&mut writer)
+                    .map_err(|why| why.to_string())?;
+            }
+            writer.into_inner().map_err(|why| why.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rps_core::outcome::CyclicPolicy;
+
+    fn user(id: u64, name: &str) -> User {
+        PlayerExport {
+            id,
+            name: format!("{}#0001", name),
+        }
+        .to_user()
+    }
+
+    #[test]
+    fn stored_game_is_rebuilt() {
+        let mut game = DiscordGame::new(
+            "K7QX".to_string(),
+            Some(GuildId(1)),
+            Language::German,
+            RuleSet::Classic,
+            1,
+            None,
+            CyclicPolicy::default(),
+        );
+        let (alice, bob) = (user(1, "alice"), user(2, "bob"));
+        game.add_player(&alice).unwrap();
+        game.add_player(&bob).unwrap();
+        game.start().unwrap();
+        game.choose(&alice, Choice::Rock).unwrap();
+        game.choose(&bob, Choice::Scissors).unwrap();
+        game.resolve_round().unwrap();
+        let export = GameExport::from(&game);
+        let stored: GameExport =
+            serde_json::from_slice(&serde_json::to_vec(&export).unwrap()).unwrap();
+        let restored = stored.to_game().unwrap();
+        assert!(restored.is_done());
+        assert_eq!(restored.get_points(), game.get_points());
+        assert_eq!(restored.get_nonce(), game.get_nonce());
+        assert_eq!(restored.get_language(), Language::German);
+        assert_eq!(restored.get_guild_id(), Some(GuildId(1)));
+        assert_eq!(
+            restored
+                .get_players()
+                .iter()
+                .find(|user| user.id.0 == 1)
+                .map(User::tag),
+            Some("alice#0001".to_string())
+        );
+    }
+
+    #[test]
+    fn game_without_events_is_not_rebuilt() {
+        let mut export = GameExport::from(&DiscordGame::new(
+            "K7QX".to_string(),
+            None,
+            Language::English,
+            RuleSet::Classic,
+            1,
+            None,
+            CyclicPolicy::default(),
+        ));
+        export.events.clear();
+        assert!(export.to_game().is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::discordgame::DiscordGame;
+use crate::Handler;
+use rps_core::state::GameState;
+use serenity::http::Http;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::sleep;
+use tracing::{info_span, Instrument};
+
+type Job = Box<dyn FnOnce(&mut DiscordGame) + Send>;
+
+enum GameCommand {
+    Run(Job),
+    End(oneshot::Sender<DiscordGame>),
+}
+
+/// A running game. The game itself lives in its own task, which applies the commands sent
+/// through this handle one after another and resolves rounds when their deadline passes.
+#[derive(Clone)]
+pub struct GameHandle {
+    sender: mpsc::UnboundedSender<GameCommand>,
+}
+
+impl GameHandle {
+    /// Runs `f` on the game inside its task and returns the result, or `None` if the task
+    /// has stopped because the game finished or panicked.
+    pub async fn call<R, F>(&self, f: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut DiscordGame) -> R + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.sender
+            .send(GameCommand::Run(Box::new(move |game| {
+                let _ = reply.send(f(game));
+            })))
+            .ok()?;
+        result.await.ok()
+    }
+    /// Stops the task and hands the game back, or returns `None` if it already stopped.
+    pub async fn end(&self) -> Option<DiscordGame> {
+        let (reply, game) = oneshot::channel();
+        self.sender.send(GameCommand::End(reply)).ok()?;
+        game.await.ok()
+    }
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// Starts the task owning `game`. Once the game is done it is handed to
+/// [`Handler::finish_game`] and the task stops.
+pub fn spawn_game(game: DiscordGame, handler: Handler, http: Arc<Http>) -> GameHandle {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let span = info_span!("game", game_id = %game.get_id());
+    tokio::spawn(run(game, receiver, handler, http).instrument(span));
+    GameHandle { sender }
+}
+
+async fn run(
+    mut game: DiscordGame,
+    mut receiver: mpsc::UnboundedReceiver<GameCommand>,
+    handler: Handler,
+    http: Arc<Http>,
+) {
+    loop {
+        let deadline = match game.get_state() {
+            GameState::Choosing | GameState::Revealing => game.get_deadline(),
+            _ => None,
+        };
+        tokio::select! {
+            command = receiver.recv() => match command {
+                Some(GameCommand::Run(job)) => job(&mut game),
+                Some(GameCommand::End(reply)) => {
+                    if let Err(game) = reply.send(game) {
+                        handler.finish_game(game);
+                    }
+                    return;
+                }
+                None => return,
+            },
+            _ = sleep_until(deadline), if deadline.is_some() => {
+                handler.expire_round(&mut game, &http);
+            }
+        }
+        if game.is_done() {
+            receiver.close();
+            while let Ok(command) = receiver.try_recv() {
+                if let GameCommand::Run(job) = command {
+                    job(&mut game);
+                }
+            }
+            handler.finish_game(game);
+            return;
+        }
+    }
+}
+
+async fn sleep_until(deadline: Option<SystemTime>) {
+    if let Some(remaining) =
+        deadline.and_then(|deadline| deadline.duration_since(SystemTime::now()).ok())
+    {
+        sleep(remaining).await;
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::{HashMap, VecDeque};
+
+/// How many finished games are kept in memory. Older ones are rebuilt from storage when
+/// they are needed again.
+pub const FINISHED_GAME_LIMIT: usize = 200;
+
+/// Keeps the most recently used entries up to a fixed number, forgetting the least recently
+/// used one first.
+pub struct GameCache<V> {
+    limit: usize,
+    entries: HashMap<String, V>,
+    /// The ids from least to most recently used.
+    order: VecDeque<String>,
+}
+
+impl<V: Clone> GameCache<V> {
+    pub fn new(limit: usize) -> Self {
+        GameCache {
+            limit,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+    fn touch(&mut self, id: &str) {
+        if let Some(index) = self.order.iter().position(|entry| entry == id) {
+            self.order.remove(index);
+        }
+        self.order.push_back(id.to_string());
+    }
+    pub fn get(&mut self, id: &str) -> Option<V> {
+        let value = self.entries.get(id)?.clone();
+        self.touch(id);
+        Some(value)
+    }
+    pub fn contains(&self, id: &str) -> bool {
+        self.entries.contains_key(id)
+    }
+    pub fn insert(&mut self, id: String, value: V) {
+        self.touch(&id);
+        self.entries.insert(id, value);
+        while self.entries.len() > self.limit {
+            match self.order.pop_front() {
+                Some(oldest) => self.entries.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_entry_is_dropped() {
+        let mut cache = GameCache::new(2);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        cache.insert("c".to_string(), 3);
+        assert!(!cache.contains("a"));
+        assert_eq!(cache.get("b"), Some(2));
+        assert_eq!(cache.get("c"), Some(3));
+    }
+
+    #[test]
+    fn used_entry_is_kept() {
+        let mut cache = GameCache::new(2);
+        cache.insert("a".to_string(), 1);
+        cache.insert("b".to_string(), 2);
+        assert_eq!(cache.get("a"), Some(1));
+        cache.insert("c".to_string(), 3);
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        // Inserting an id again does not count it twice.
+        cache.insert("a".to_string(), 4);
+        assert_eq!(cache.get("a"), Some(4));
+        assert!(cache.contains("c"));
+    }
+}
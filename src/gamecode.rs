@@ -0,0 +1,65 @@
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+
+/// Letters and digits that cannot be mistaken for each other, so `0`, `O`, `1` and `I` are
+/// left out.
+const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+/// The length of a new code. Longer codes are only used when many short ones are taken.
+const CODE_LENGTH: usize = 4;
+/// How many random codes of one length are tried before trying a longer one.
+const ATTEMPTS: usize = 16;
+
+fn random_code(length: usize) -> String {
+    let mut rng = thread_rng();
+    (0..length)
+        .filter_map(|_| ALPHABET.choose(&mut rng).map(|c| *c as char))
+        .collect()
+}
+
+/// Creates a short code such as `K7QX` for which `is_taken` returns false.
+pub fn generate_code(is_taken: impl Fn(&str) -> bool) -> String {
+    (CODE_LENGTH..)
+        .flat_map(|length| (0..ATTEMPTS).map(move |_| random_code(length)))
+        .find(|code| !is_taken(code))
+        .unwrap_or_default()
+}
+
+/// Turns a code as typed by a player into the form it was created in.
+pub fn normalize_code(code: &str) -> String {
+    code.trim().trim_start_matches('#').to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_use_the_alphabet() {
+        for _ in 0..100 {
+            let code = generate_code(|_| false);
+            assert_eq!(code.len(), CODE_LENGTH);
+            assert!(code.bytes().all(|c| ALPHABET.contains(&c)), "{}", code);
+        }
+        assert!(!ALPHABET.iter().any(|c| b"0O1I".contains(c)));
+    }
+
+    #[test]
+    fn longer_code_when_short_ones_are_taken() {
+        let code = generate_code(|code| code.len() < CODE_LENGTH + 2);
+        assert_eq!(code.len(), CODE_LENGTH + 2);
+    }
+
+    #[test]
+    fn taken_code_is_skipped() {
+        let first = generate_code(|_| false);
+        for _ in 0..100 {
+            assert_ne!(generate_code(|code| code == first), first);
+        }
+    }
+
+    #[test]
+    fn typed_code_is_normalized() {
+        assert_eq!(normalize_code("#k7qx "), "K7QX");
+        assert_eq!(normalize_code("  K7QX"), "K7QX");
+    }
+}
@@ -0,0 +1,381 @@
+use crate::i18n::Language;
+use itertools::Itertools;
+use rps_core::outcome::CyclicPolicy;
+use rps_core::ruleset::RuleSet;
+use serde::{Deserialize, Serialize};
+use serenity::builder::CreateEmbed;
+use serenity::model::id::{ChannelId, RoleId};
+use serenity::model::mention::Mentionable;
+
+pub const MAX_ROUNDS: u64 = 100;
+pub const MIN_ROUND_TIMEOUT: u64 = 10;
+pub const MAX_ROUND_TIMEOUT: u64 = 3600;
+pub const KEYS: [&str; 9] = [
+    "default-rounds",
+    "max-rounds",
+    "round-timeout",
+    "allowed-modes",
+    "default-rule-set",
+    "allowed-channels",
+    "allowed-roles",
+    "language",
+    "cyclic-policy",
+];
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildConfig {
+    pub default_rounds: u64,
+    pub max_rounds: u64,
+    pub round_timeout: Option<u64>,
+    pub allowed_modes: Vec<RuleSet>,
+    pub default_rule_set: RuleSet,
+    pub allowed_channels: Vec<ChannelId>,
+    pub allowed_roles: Vec<RoleId>,
+    pub language: Option<Language>,
+    pub cyclic_policy: CyclicPolicy,
+}
+
+impl Default for GuildConfig {
+    fn default() -> Self {
+        GuildConfig {
+            default_rounds: 1,
+            max_rounds: 15,
+            round_timeout: None,
+            allowed_modes: RuleSet::all().to_vec(),
+            default_rule_set: RuleSet::default(),
+            allowed_channels: vec![],
+            allowed_roles: vec![],
+            language: None,
+            cyclic_policy: CyclicPolicy::default(),
+        }
+    }
+}
+
+fn parse_rounds(value: &str, language: Language) -> Result<u64, String> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|rounds| (1..=MAX_ROUNDS).contains(rounds))
+        .ok_or_else(|| language.format("config-invalid-rounds", &[("max", MAX_ROUNDS.into())]))
+}
+
+fn parse_rule_set(value: &str, language: Language) -> Result<RuleSet, String> {
+    RuleSet::from_name(value.trim()).ok_or_else(|| {
+        language.format(
+            "config-invalid-rule-set",
+            &[(
+                "rule_sets",
+                RuleSet::all()
+                    .into_iter()
+                    .map(|rule_set| rule_set.name())
+                    .join(", ")
+                    .into(),
+            )],
+        )
+    })
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|item| !item.is_empty())
+}
+
+impl GuildConfig {
+    /// The rounds of a new game, limited to `max_rounds`. Returns whether the requested
+    /// rounds had to be lowered, so the player can be told.
+    pub fn rounds(&self, requested: Option<u64>) -> (u64, bool) {
+        let max_rounds = self.max_rounds.max(1);
+        let rounds = requested.unwrap_or(self.default_rounds).max(1);
+        (
+            rounds.min(max_rounds),
+            requested.is_some() && rounds > max_rounds,
+        )
+    }
+    /// Replaces settings of a stored config that `set` would have refused, e.g. because the
+    /// file was edited by hand.
+    pub fn repair(&mut self) {
+        let default = GuildConfig::default();
+        if !(1..=MAX_ROUNDS).contains(&self.max_rounds) {
+            self.max_rounds = default.max_rounds;
+        }
+        if !(1..=MAX_ROUNDS).contains(&self.default_rounds) {
+            self.default_rounds = default.default_rounds;
+        }
+        if self
+            .round_timeout
+            .is_some_and(|seconds| !(MIN_ROUND_TIMEOUT..=MAX_ROUND_TIMEOUT).contains(&seconds))
+        {
+            self.round_timeout = default.round_timeout;
+        }
+        if self.allowed_modes.is_empty() {
+            self.allowed_modes = default.allowed_modes;
+        }
+        if !self.allows_mode(self.default_rule_set) {
+            self.default_rule_set = self.allowed_modes[0];
+        }
+    }
+    pub fn allows_mode(&self, rule_set: RuleSet) -> bool {
+        self.allowed_modes.contains(&rule_set)
+    }
+    pub fn allows_channel(&self, channel_id: ChannelId) -> bool {
+        self.allowed_channels.is_empty() || self.allowed_channels.contains(&channel_id)
+    }
+    pub fn allows_roles(&self, roles: &[RoleId]) -> bool {
+        self.allowed_roles.is_empty() || roles.iter().any(|role| self.allowed_roles.contains(role))
+    }
+    pub fn check_access(
+        &self,
+        channel_id: ChannelId,
+        roles: &[RoleId],
+        language: Language,
+    ) -> Result<(), String> {
+        if !self.allows_channel(channel_id) {
+            return Err(language.format(
+                "channel-not-allowed",
+                &[(
+                    "channels",
+                    self.describe("allowed-channels", language).into(),
+                )],
+            ));
+        }
+        if !self.allows_roles(roles) {
+            return Err(language.format(
+                "role-not-allowed",
+                &[("roles", self.describe("allowed-roles", language).into())],
+            ));
+        }
+        Ok(())
+    }
+    pub fn set(&mut self, key: &str, value: &str, language: Language) -> Result<(), String> {
+        match key {
+            "default-rounds" => self.default_rounds = parse_rounds(value, language)?,
+            "max-rounds" => self.max_rounds = parse_rounds(value, language)?,
+            "round-timeout" => {
+                self.round_timeout = match value.trim() {
+                    "0" | "off" => None,
+                    seconds => Some(
+                        seconds
+                            .parse()
+                            .ok()
+                            .filter(|seconds| {
+                                (MIN_ROUND_TIMEOUT..=MAX_ROUND_TIMEOUT).contains(seconds)
+                            })
+                            .ok_or_else(|| {
+                                language.format(
+                                    "config-invalid-timeout",
+                                    &[
+                                        ("min", MIN_ROUND_TIMEOUT.into()),
+                                        ("max", MAX_ROUND_TIMEOUT.into()),
+                                    ],
+                                )
+                            })?,
+                    ),
+                }
+            }
+            "allowed-modes" => {
+                let modes = split_list(value)
+                    .map(|name| parse_rule_set(name, language))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if modes.is_empty() {
+                    return Err(language.text("config-no-modes"));
+                }
+                self.allowed_modes = modes.into_iter().unique().collect();
+            }
+            "default-rule-set" => self.default_rule_set = parse_rule_set(value, language)?,
+            "allowed-channels" => {
+                self.allowed_channels = match value.trim() {
+                    "" | "all" => vec![],
+                    channels => split_list(channels)
+                        .map(|channel| {
+                            channel
+                                .trim_start_matches("<#")
+                                .trim_end_matches('>')
+                                .parse()
+                                .map(ChannelId)
+                                .map_err(|_| {
+                                    language.format(
+                                        "config-invalid-channel",
+                                        &[("channel", channel.to_string().into())],
+                                    )
+                                })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .unique()
+                        .collect(),
+                }
+            }
+            "allowed-roles" => {
+                self.allowed_roles = match value.trim() {
+                    "" | "all" => vec![],
+                    roles => split_list(roles)
+                        .map(|role| {
+                            role.trim_start_matches("<@&")
+                                .trim_end_matches('>')
+                                .parse()
+                                .map(RoleId)
+                                .map_err(|_| {
+                                    language.format(
+                                        "config-invalid-role",
+                                        &[("role", role.to_string().into())],
+                                    )
+                                })
+                        })
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into_iter()
+                        .unique()
+                        .collect(),
+                }
+            }
+            "language" => {
+                self.language = match value.trim() {
+                    "auto" => None,
+                    code => Some(
+                        Language::from_code(code)
+                            .ok_or_else(|| language.text("config-invalid-language"))?,
+                    ),
+                }
+            }
+            "cyclic-policy" => {
+                self.cyclic_policy = CyclicPolicy::from_name(value.trim()).ok_or_else(|| {
+                    language.format(
+                        "config-invalid-cyclic-policy",
+                        &[(
+                            "policies",
+                            CyclicPolicy::all()
+                                .into_iter()
+                                .map(|policy| policy.name())
+                                .join(", ")
+                                .into(),
+                        )],
+                    )
+                })?
+            }
+            _ => return Err(language.text("config-unknown-key")),
+        }
+        if !self.allowed_modes.contains(&self.default_rule_set) {
+            return Err(language.text("config-default-not-allowed"));
+        }
+        Ok(())
+    }
+    pub fn reset(&mut self, key: Option<&str>) {
+        let default = GuildConfig::default();
+        match key {
+            Some("default-rounds") => self.default_rounds = default.default_rounds,
+            Some("max-rounds") => self.max_rounds = default.max_rounds,
+            Some("round-timeout") => self.round_timeout = default.round_timeout,
+            Some("allowed-modes") => self.allowed_modes = default.allowed_modes,
+            Some("default-rule-set") => {
+                self.default_rule_set = match self.allows_mode(default.default_rule_set) {
+                    true => default.default_rule_set,
+                    false => self.allowed_modes[0],
+                }
+            }
+            Some("allowed-channels") => self.allowed_channels = default.allowed_channels,
+            Some("allowed-roles") => self.allowed_roles = default.allowed_roles,
+            Some("language") => self.language = default.language,
+            Some("cyclic-policy") => self.cyclic_policy = default.cyclic_policy,
+            _ => *self = default,
+        }
+    }
+    pub fn describe(&self, key: &str, language: Language) -> String {
+        match key {
+            "default-rounds" => self.default_rounds.to_string(),
+            "max-rounds" => self.max_rounds.to_string(),
+            "round-timeout" => match self.round_timeout {
+                Some(seconds) => format!("{}s", seconds),
+                None => language.text("config-off"),
+            },
+            "allowed-modes" => self
+                .allowed_modes
+                .iter()
+                .map(|rule_set| language.rule_set(*rule_set))
+                .join(", "),
+            "default-rule-set" => language.rule_set(self.default_rule_set),
+            "allowed-channels" => match self.allowed_channels.is_empty() {
+                true => language.text("config-all-channels"),
+                false => self
+                    .allowed_channels
+                    .iter()
+                    .map(|channel| channel.mention().to_string())
+                    .join(" "),
+            },
+            "allowed-roles" => match self.allowed_roles.is_empty() {
+                true => language.text("config-all-roles"),
+                false => self
+                    .allowed_roles
+                    .iter()
+                    .map(|role| role.mention().to_string())
+                    .join(" "),
+            },
+            "language" => match self.language {
+                Some(language) => language.code().to_string(),
+                None => language.text("config-auto"),
+            },
+            "cyclic-policy" => {
+                language.text(&format!("cyclic-policy-{}", self.cyclic_policy.name()))
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+pub fn generate_config_embed<'a>(
+    config: &GuildConfig,
+    language: Language,
+    embed: &'a mut CreateEmbed,
+) -> &'a mut CreateEmbed {
+    embed.title(language.text("config-title"));
+    KEYS.into_iter().for_each(|key| {
+        embed.field(
+            format!(
+                "{} (`{}`)",
+                language.text(&format!("config-key-{}", key)),
+                key
+            ),
+            config.describe(key, language),
+            true,
+        );
+    });
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_are_capped() {
+        let config = GuildConfig {
+            default_rounds: 3,
+            max_rounds: 5,
+            ..GuildConfig::default()
+        };
+        assert_eq!(config.rounds(None), (3, false));
+        assert_eq!(config.rounds(Some(0)), (1, false));
+        assert_eq!(config.rounds(Some(5)), (5, false));
+        assert_eq!(config.rounds(Some(8)), (5, true));
+    }
+
+    #[test]
+    fn broken_config_is_repaired() {
+        let mut config: GuildConfig = serde_json::from_value(serde_json::json!({
+            "default_rounds": 0,
+            "max_rounds": 0,
+            "round_timeout": 1,
+            "allowed_modes": [],
+        }))
+        .unwrap();
+        assert_eq!(config.rounds(None), (1, false));
+        config.repair();
+        let default = GuildConfig::default();
+        assert_eq!(config.default_rounds, default.default_rounds);
+        assert_eq!(config.max_rounds, default.max_rounds);
+        assert_eq!(config.round_timeout, None);
+        assert_eq!(config.allowed_modes, default.allowed_modes);
+        assert!(config.allows_mode(config.default_rule_set));
+    }
+}
@@ -1,23 +1,107 @@
-use crate::choice::Choice;
+use crate::i18n::Language;
+use itertools::Itertools;
+use rps_core::choice::Choice;
+use rps_core::choiceresult::ChoiceResult;
+use rps_core::round::Round;
+use serenity::model::application::interaction::application_command::CommandDataOption;
 use serenity::model::prelude::ReactionType;
+use serenity::model::user::User;
+use serenity::utils::MessageBuilder;
 
-pub fn get_choice_from_char(c: char) -> Option<Choice> {
-    match c {
-        'r' => Some(Choice::Rock),
-        'p' => Some(Choice::Paper),
-        's' => Some(Choice::Scissors),
-        'l' => Some(Choice::Lizard),
-        'S' => Some(Choice::Spock),
-        _ => None,
+/// The most characters Discord shows in an embed field.
+pub const FIELD_LIMIT: usize = 1024;
+
+pub fn choice_to_emoji(c: Choice) -> ReactionType {
+    ReactionType::Unicode(c.emoji().to_string())
+}
+
+pub fn get_string_option(options: &[CommandDataOption], name: &str) -> Option<String> {
+    options
+        .iter()
+        .find(|option| option.name == name)
+        .and_then(|option| Some(option.value.as_ref()?.as_str()?.to_string()))
+}
+
+/// Joins `lines` until the next one would make the text longer than `limit` characters and
+/// ends the text with a note on how many lines were left out.
+pub fn fit_lines(
+    lines: impl ExactSizeIterator<Item = String>,
+    limit: usize,
+    language: Language,
+) -> String {
+    let total = lines.len();
+    let mut text = String::new();
+    for (shown, line) in lines.enumerate() {
+        let more = language.format("and-more", &[("count", (total - shown).into())]);
+        if text.chars().count() + line.chars().count() + more.chars().count() > limit {
+            return text + &more;
+        }
+        text.push_str(&line);
     }
+    text
 }
 
-pub fn choice_to_emoji(c: Choice) -> ReactionType {
-    match c {
-        Choice::Rock => ReactionType::Unicode("🪨".to_string()),
-        Choice::Paper => ReactionType::Unicode("📄".to_string()),
-        Choice::Scissors => ReactionType::Unicode("✂️".to_string()),
-        Choice::Lizard => ReactionType::Unicode("🦎".to_string()),
-        Choice::Spock => ReactionType::Unicode("🖖".to_string()),
+pub fn choice_result_message(result: &ChoiceResult, language: Language) -> String {
+    MessageBuilder::new()
+        .push(format!("{} × ", result.winners))
+        .push(choice_to_emoji(result.winner_choice))
+        .push(" ")
+        .push(language.verb(&result.verb))
+        .push(" ")
+        .push(format!("{} × ", result.losers))
+        .push(choice_to_emoji(result.loser_choice))
+        .push("\n")
+        .build()
+}
+
+pub fn round_choice_list(round: &Round<User>, language: Language) -> String {
+    fit_lines(
+        round
+            .choices
+            .iter()
+            .sorted_by_key(|(user, _)| user.id)
+            .map(|(user, choice)| {
+                MessageBuilder::new()
+                    .mention(user)
+                    .push(" ")
+                    .push(choice_to_emoji(*choice))
+                    .push("\n")
+                    .build()
+            }),
+        FIELD_LIMIT,
+        language,
+    )
+}
+
+pub fn round_result_list(round: &Round<User>, language: Language) -> String {
+    if round.results.is_empty() {
+        return language.text("no-one-wins");
+    }
+    round
+        .results
+        .iter()
+        .map(|result| choice_result_message(result, language))
+        .collect()
+}
+
+pub fn round_point_list(round: &Round<User>, language: Language) -> String {
+    if round.points.is_empty() {
+        return language.text("no-points");
     }
+    fit_lines(
+        round
+            .points
+            .iter()
+            .sorted_by_key(|(user, points)| (std::cmp::Reverse(**points), user.id))
+            .map(|(user, points)| {
+                MessageBuilder::new()
+                    .mention(user)
+                    .push(" ")
+                    .push(language.format("round-points", &[("points", (*points).into())]))
+                    .push("\n")
+                    .build()
+            }),
+        FIELD_LIMIT,
+        language,
+    )
 }
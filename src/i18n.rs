@@ -0,0 +1,147 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use rps_core::choice::Choice;
+use rps_core::ruleset::RuleSet;
+use rps_core::state::GameError;
+use serde::{Deserialize, Serialize};
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+const ENGLISH: &str = include_str!("../locales/en/main.ftl");
+const GERMAN: &str = include_str!("../locales/de/main.ftl");
+
+static ENGLISH_BUNDLE: OnceLock<FluentBundle<FluentResource>> = OnceLock::new();
+static GERMAN_BUNDLE: OnceLock<FluentBundle<FluentResource>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Language {
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+fn create_bundle(language: &str, source: &str) -> FluentBundle<FluentResource> {
+    let language: LanguageIdentifier = language.parse().expect("invalid language identifier");
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|(_, errors)| panic!("invalid message catalog: {:?}", errors));
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|errors| panic!("duplicate messages in catalog: {:?}", errors));
+    bundle
+}
+
+impl Language {
+    pub fn all() -> [Language; 2] {
+        [Language::English, Language::German]
+    }
+    pub fn from_locale(locale: &str) -> Self {
+        match locale.split('-').next() {
+            Some("de") => Language::German,
+            _ => Language::English,
+        }
+    }
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "de" => Some(Language::German),
+            _ => None,
+        }
+    }
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+    pub fn from_interaction(locale: &str, guild_locale: Option<&String>) -> Self {
+        Self::from_locale(guild_locale.map(String::as_str).unwrap_or(locale))
+    }
+    pub fn locale(&self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::German => "de",
+        }
+    }
+    fn bundle(&self) -> &'static FluentBundle<FluentResource> {
+        match self {
+            Language::English => ENGLISH_BUNDLE.get_or_init(|| create_bundle("en-US", ENGLISH)),
+            Language::German => GERMAN_BUNDLE.get_or_init(|| create_bundle("de", GERMAN)),
+        }
+    }
+    pub fn format(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let bundle = self.bundle();
+        let pattern = match bundle.get_message(id).and_then(|message| message.value()) {
+            Some(pattern) => pattern,
+            None if *self != Language::English => return Language::English.format(id, args),
+            None => return id.to_string(),
+        };
+        let mut fluent_args = FluentArgs::new();
+        args.iter()
+            .for_each(|(name, value)| fluent_args.set(*name, value.clone()));
+        let mut errors = vec![];
+        bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .to_string()
+    }
+    pub fn text(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+    pub fn choice(&self, choice: Choice) -> String {
+        self.text(&format!("choice-{}", choice.name().to_lowercase()))
+    }
+    pub fn rule_set(&self, rule_set: RuleSet) -> String {
+        self.text(&format!("rule-set-{}", rule_set.name()))
+    }
+    pub fn verb(&self, verb: &str) -> String {
+        self.text(&format!("verb-{}", verb))
+    }
+    pub fn game_error(&self, error: GameError) -> String {
+        self.text(&format!("game-error-{}", error.name()))
+    }
+}
+
+pub fn localize_command<'a>(
+    command: &'a mut CreateApplicationCommand,
+    id: &str,
+) -> &'a mut CreateApplicationCommand {
+    command
+        .name(Language::English.text(&format!("{}-name", id)))
+        .description(Language::English.text(&format!("{}-description", id)));
+    Language::all()
+        .into_iter()
+        .filter(|language| *language != Language::English)
+        .for_each(|language| {
+            command
+                .name_localized(language.locale(), language.text(&format!("{}-name", id)))
+                .description_localized(
+                    language.locale(),
+                    language.text(&format!("{}-description", id)),
+                );
+        });
+    command
+}
+
+pub fn localize_option<'a>(
+    option: &'a mut CreateApplicationCommandOption,
+    id: &str,
+) -> &'a mut CreateApplicationCommandOption {
+    option
+        .name(Language::English.text(&format!("{}-name", id)))
+        .description(Language::English.text(&format!("{}-description", id)));
+    Language::all()
+        .into_iter()
+        .filter(|language| *language != Language::English)
+        .for_each(|language| {
+            option
+                .name_localized(language.locale(), language.text(&format!("{}-name", id)))
+                .description_localized(
+                    language.locale(),
+                    language.text(&format!("{}-description", id)),
+                );
+        });
+    option
+}
@@ -1,324 +1,1297 @@
 extern crate tokio;
-mod battle;
-mod battleresult;
-mod choice;
-mod game;
+mod analytics;
+mod commands;
+mod component;
+mod config;
+mod discordgame;
+mod export;
+mod gameactor;
+mod gamecache;
+mod gamecode;
+mod guildconfig;
 mod helper;
-use crate::game::Game;
+mod i18n;
+mod metrics;
+mod outbox;
+mod response;
+mod server;
+mod storage;
+use crate::analytics::{picks_from_round, Analysis};
+use crate::commands::{sync_commands, CommandScope};
+use crate::component::{ComponentAction, ComponentId};
+use crate::config::Config;
+use crate::discordgame::DiscordGame;
+use crate::export::{export_games, ExportFormat, GameExport};
+use crate::gameactor::{spawn_game, GameHandle};
+use crate::gamecache::{GameCache, FINISHED_GAME_LIMIT};
+use crate::gamecode::{generate_code, normalize_code};
+use crate::guildconfig::{generate_config_embed, GuildConfig};
+use crate::helper::{choice_to_emoji, get_string_option};
+use crate::i18n::Language;
+use crate::metrics::Metrics;
+use crate::outbox::{MessageEdit, Outbox};
+use crate::response::{Reply, Responder};
+use crate::storage::Storage;
 use dotenv::dotenv;
-use rand::random;
+use rps_core::battle::Battle;
+use rps_core::choice::Choice;
+use rps_core::ruleset::RuleSet;
+use rps_core::state::{GameError, GameState};
 use serenity::async_trait;
-use serenity::builder::CreateComponents;
-use serenity::model::prelude::ReactionType;
-use serenity::utils::MessageBuilder;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::client::bridge::gateway::event::ShardStageUpdateEvent;
+use serenity::client::bridge::gateway::ShardId;
+use serenity::gateway::ConnectionStage;
+use serenity::http::Http;
+use serenity::model::application::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOptionValue,
+};
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::id::{ChannelId, GuildId, MessageId};
+use serenity::model::user::User;
 use serenity::{
     client::EventHandler,
-    model::application::{
-        command::Command, component::ButtonStyle, interaction::Interaction,
-        interaction::InteractionResponseType,
-    },
+    model::application::{component::ButtonStyle, interaction::Interaction},
     model::gateway::Ready,
-    prelude::{Context, GatewayIntents},
-    Client,
+    prelude::Context,
+    Client, Error as SerenityError,
 };
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex, RwLock};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::oneshot;
+use tracing::{debug, error, field, info, info_span, warn, Instrument, Span};
+use tracing_subscriber::filter::LevelFilter;
 
+#[derive(Clone)]
 struct Handler {
-    games: RwLock<HashMap<String, Arc<Mutex<Game>>>>,
+    games: Arc<RwLock<HashMap<String, GameHandle>>>,
+    finished_games: Arc<RwLock<GameCache<Arc<DiscordGame>>>>,
+    configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>,
+    storage: Storage,
+    command_scope: CommandScope,
+    metrics: Metrics,
+    outbox: Outbox,
+    /// Whether each shard is connected to the gateway.
+    shards: Arc<RwLock<HashMap<ShardId, bool>>>,
+    shutting_down: Arc<AtomicBool>,
+    admin_token: Option<String>,
 }
 
 trait New {
-    fn new() -> Self;
+    fn new(config: &Config) -> Self;
 }
 
 impl New for Handler {
-    fn new() -> Self {
+    fn new(config: &Config) -> Self {
         Handler {
-            games: RwLock::new(HashMap::new()),
+            games: Arc::new(RwLock::new(HashMap::new())),
+            finished_games: Arc::new(RwLock::new(GameCache::new(FINISHED_GAME_LIMIT))),
+            configs: Arc::new(RwLock::new(HashMap::new())),
+            storage: Storage::new(config.storage_path.clone()),
+            command_scope: match config.dev_guild_id {
+                Some(guild_id) => CommandScope::Guild(guild_id),
+                None => CommandScope::Global,
+            },
+            metrics: Metrics::new(),
+            outbox: Outbox::default(),
+            shards: Arc::new(RwLock::new(HashMap::new())),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+            admin_token: config.admin_token.clone(),
         }
     }
 }
 
 impl Handler {
-    fn delete_game(&self, id: &String) -> bool {
+    /// Whether every shard is connected to the gateway.
+    fn is_connected(&self) -> bool {
+        self.shards
+            .read()
+            .is_ok_and(|shards| !shards.is_empty() && shards.values().all(|connected| *connected))
+    }
+    /// Stores a game that has ended and keeps it around for the History button. Only the
+    /// most recently used finished games stay in memory, see [`Handler::restore_game`].
+    fn finish_game(&self, game: DiscordGame) {
+        let id = game.get_id().clone();
+        if let Err(why) = self.storage.save_game(&GameExport::from(&game)) {
+            error!(game_id = %id, error = %why, "Cannot store game");
+        }
+        if game.is_done() {
+            self.metrics.games_finished.inc();
+        }
+        if let Ok(finished_games) = self.finished_games.write().as_deref_mut() {
+            finished_games.insert(id.clone(), Arc::new(game));
+        }
         if let Ok(games) = self.games.write().as_deref_mut() {
-            return games.remove(id).is_some();
+            games.remove(&id);
         }
-        false
+        info!(game_id = %id, "Game finished");
     }
-    fn new_game(&self, rounds: u64) -> Option<String> {
-        let id = random::<u128>().to_string();
-        if let Ok(games) = self.games.write().as_deref_mut() {
-            games.insert(
+    /// Ends a running game early, stores it like a finished one and replaces the buttons of
+    /// its message with the `notice` message. Returns false if no such game is running.
+    async fn end_game_early(&self, http: &Arc<Http>, id: &String, notice: &str) -> bool {
+        let game = match self.get_handle(id) {
+            Some(handle) => handle.end().await,
+            None => None,
+        };
+        let mut game = match game {
+            Some(game) => game,
+            None => return false,
+        };
+        let mut embed = CreateEmbed::default();
+        game.generate_embed(&mut embed);
+        let (message, language) = (game.get_message(), game.get_language());
+        if let Err(why) = game.cancel() {
+            warn!(game_id = %id, error = %why, "Cannot cancel game");
+        }
+        self.finish_game(game);
+        info!(game_id = %id, reason = notice, "Game ended early");
+        if let Some(message) = message {
+            self.queue_edit(
+                http.clone(),
+                id,
+                message,
+                MessageEdit {
+                    content: Some(language.text(notice)),
+                    embed,
+                    components: CreateComponents::default(),
+                },
+            );
+        }
+        true
+    }
+    fn check_open(&self, language: Language) -> Result<(), String> {
+        match self.shutting_down.load(Ordering::Relaxed) {
+            true => Err(language.text("bot-restarting")),
+            false => Ok(()),
+        }
+    }
+    /// Stops accepting new games and ends every running one, telling its players that the
+    /// bot is restarting. The rounds played so far are stored with the game.
+    async fn shutdown(&self, http: &Arc<Http>) {
+        self.shutting_down.store(true, Ordering::Relaxed);
+        let ids: Vec<String> = match self.games.read() {
+            Ok(games) => games.keys().cloned().collect(),
+            Err(_) => vec![],
+        };
+        info!(games = ids.len(), "Ending running games");
+        for id in ids {
+            self.end_game_early(http, &id, "bot-restarting").await;
+        }
+        self.flush_edits(Duration::from_secs(10)).await;
+    }
+    fn new_game(
+        &self,
+        http: Arc<Http>,
+        guild_id: Option<GuildId>,
+        language: Language,
+        rule_set: RuleSet,
+        rounds: u64,
+        config: &GuildConfig,
+    ) -> Option<String> {
+        let in_memory = |code: &str| {
+            self.games
+                .read()
+                .is_ok_and(|games| games.contains_key(code))
+                || self
+                    .finished_games
+                    .read()
+                    .is_ok_and(|finished_games| finished_games.contains(code))
+        };
+        loop {
+            // Games are looked up by their code alone, so a code stored for any server is
+            // taken. Storage is checked before taking the lock, so only the in-memory games
+            // have to be checked again in case another game took the code meanwhile.
+            let id =
+                generate_code(|code| in_memory(code) || self.storage.find_game(code).is_some());
+            let mut games = self.games.write().ok()?;
+            if games.contains_key(&id)
+                || self
+                    .finished_games
+                    .read()
+                    .is_ok_and(|finished_games| finished_games.contains(&id))
+            {
+                continue;
+            }
+            let game = DiscordGame::new(
                 id.clone(),
-                Arc::new(Mutex::new(Game::new(id.clone(), rounds))),
+                guild_id,
+                language,
+                rule_set,
+                rounds,
+                config.round_timeout,
+                config.cyclic_policy,
             );
+            games.insert(id.clone(), spawn_game(game, self.clone(), http));
+            self.metrics.games_created.inc();
             return Some(id);
         }
-        None
     }
-    fn get_game(&self, id: &String) -> Option<Arc<Mutex<Game>>> {
-        if let Ok(games) = self.games.read() {
-            return Some(games.get(id)?.to_owned());
+    fn get_config(&self, guild_id: Option<GuildId>) -> GuildConfig {
+        let guild_id = match guild_id {
+            Some(guild_id) => guild_id,
+            None => return GuildConfig::default(),
+        };
+        if let Some(config) = self
+            .configs
+            .read()
+            .ok()
+            .and_then(|configs| configs.get(&guild_id).cloned())
+        {
+            return config;
+        }
+        let config = self
+            .storage
+            .load_guild_config(guild_id.0)
+            .unwrap_or_default();
+        if let Ok(configs) = self.configs.write().as_deref_mut() {
+            configs.insert(guild_id, config.clone());
+        }
+        config
+    }
+    fn save_config(&self, guild_id: GuildId, config: GuildConfig) {
+        if let Err(why) = self.storage.save_guild_config(guild_id.0, &config) {
+            error!(guild_id = guild_id.0, error = %why, "Cannot store guild config");
+        }
+        if let Ok(configs) = self.configs.write().as_deref_mut() {
+            configs.insert(guild_id, config);
+        }
+    }
+    fn resolve_round(&self, game: &mut DiscordGame) -> Result<(), GameError> {
+        game.resolve_round()?;
+        info!(
+            game_id = %game.get_id(),
+            round = game.get_history().len(),
+            rule_set = game.get_rule_set().name(),
+            "Round resolved"
+        );
+        self.metrics
+            .rounds
+            .with_label_values(&[game.get_rule_set().name()])
+            .inc();
+        self.record_round(game);
+        Ok(())
+    }
+    /// Resolves the current round of `game` after its deadline passed and shows the result
+    /// in the game's message. Called from the game's own task.
+    fn expire_round(&self, game: &mut DiscordGame, http: &Arc<Http>) {
+        info!(game_id = %game.get_id(), round = game.get_round(), "Round timed out");
+        if let Err(why) = self.resolve_round(game) {
+            warn!(game_id = %game.get_id(), error = %why, "Cannot resolve round after a timeout");
+            return;
+        }
+        if game.is_done() {
+            self.metrics.games_expired.inc();
+        }
+        self.update_message(http, game);
+    }
+    /// Queues an edit of the message of `game` that shows its current state. This has to be
+    /// called inside the game's task, so edits reach the outbox in the order the game
+    /// changed and a newer state is never replaced by an older one.
+    fn update_message(&self, http: &Arc<Http>, game: &DiscordGame) {
+        let message = match game.get_message() {
+            Some(message) => message,
+            None => return,
+        };
+        let (embed, components) = match game.get_state() {
+            GameState::Lobby => render_lobby(game),
+            _ => render_game(game),
+        };
+        self.queue_edit(
+            http.clone(),
+            game.get_id(),
+            message,
+            MessageEdit {
+                content: None,
+                embed,
+                components,
+            },
+        );
+    }
+    /// Called when the message of a game cannot be edited anymore, for example because it
+    /// was deleted. The game is posted again in the same channel, or ended if the bot cannot
+    /// post there either.
+    async fn message_failed(
+        &self,
+        http: &Arc<Http>,
+        id: &String,
+        (channel_id, message_id): (ChannelId, MessageId),
+        why: SerenityError,
+    ) {
+        warn!(game_id = %id, error = %why, "Cannot update game message");
+        let view = self
+            .call_game(id, move |game| {
+                (game.get_message() == Some((channel_id, message_id))).then(|| {
+                    match game.get_state() {
+                        GameState::Lobby => render_lobby(game),
+                        _ => render_game(game),
+                    }
+                })
+            })
+            .await
+            .flatten();
+        let (embed, components) = match view {
+            Some(view) => view,
+            None => return,
+        };
+        match channel_id
+            .send_message(http, |message| {
+                message.set_embed(embed).set_components(components)
+            })
+            .await
+        {
+            Ok(message) => {
+                info!(game_id = %id, "Posted game message again");
+                self.call_game(id, move |game| game.set_message(channel_id, message.id))
+                    .await;
+            }
+            Err(why) => {
+                warn!(game_id = %id, error = %why, "Cannot post game message again");
+                self.end_game_early(http, id, "game-unreachable").await;
+            }
+        }
+    }
+    fn record_round(&self, game: &DiscordGame) {
+        if let Some(round) = game.get_history().last() {
+            let guild_id = game.get_guild_id().map(|guild_id| guild_id.0);
+            picks_from_round(game.get_id(), round)
+                .into_iter()
+                .for_each(|(user_id, pick)| {
+                    if let Err(why) = self.storage.append_pick(guild_id, user_id.0, &pick) {
+                        error!(user_id = user_id.0, error = %why, "Cannot store pick");
+                    }
+                });
+        }
+    }
+    fn export_game(
+        &self,
+        guild_id: Option<u64>,
+        id: &String,
+        format: ExportFormat,
+        language: Language,
+    ) -> Result<(String, Vec<u8>), String> {
+        if self.get_handle(id).is_some() {
+            return Err(language.text("game-running"));
+        }
+        let mut game = self
+            .get_finished_game(id)
+            .map(|game| GameExport::from(&*game))
+            .filter(|game| game.guild_id == guild_id)
+            .or_else(|| self.storage.load_game(guild_id, id))
+            .ok_or_else(|| language.text("game-not-found"))?;
+        game.nonce = None;
+        Ok((
+            format!("game-{}.{}", id, format.extension()),
+            export_games(&[game], format)?,
+        ))
+    }
+    fn export_guild(
+        &self,
+        guild_id: Option<u64>,
+        format: ExportFormat,
+        language: Language,
+    ) -> Result<(String, Vec<u8>), String> {
+        let mut games = self.storage.load_games(guild_id);
+        games.iter_mut().for_each(|game| game.nonce = None);
+        if games.is_empty() {
+            return Err(language.text("no-stored-games"));
+        }
+        Ok((
+            format!("games.{}", format.extension()),
+            export_games(&games, format)?,
+        ))
+    }
+    fn response_failed(&self, kind: &str, why: SerenityError) {
+        warn!(error = %why, "Cannot respond to {}", kind);
+        self.metrics
+            .interaction_errors
+            .with_label_values(&[kind])
+            .inc();
+    }
+    /// Answers an interaction with the reply `work` produces, deferring it while the work is
+    /// slow. `ephemeral` is whether a deferred command shows its placeholder only to the user.
+    async fn respond(
+        &self,
+        http: &Http,
+        responder: Responder<'_>,
+        ephemeral: bool,
+        work: impl Future<Output = Reply>,
+    ) -> bool {
+        match responder.respond(http, ephemeral, work).await {
+            Ok(()) => true,
+            Err(why) => {
+                self.response_failed(responder.kind(), why);
+                false
+            }
+        }
+    }
+    /// Answers an interaction with a reply that is already known.
+    async fn reply(&self, http: &Http, responder: Responder<'_>, reply: Reply) {
+        let ephemeral = reply.is_ephemeral();
+        self.respond(http, responder, ephemeral, async { reply })
+            .await;
+    }
+    fn get_handle(&self, id: &String) -> Option<GameHandle> {
+        self.games.read().ok()?.get(id).cloned()
+    }
+    fn get_finished_game(&self, id: &str) -> Option<Arc<DiscordGame>> {
+        self.finished_games.write().ok()?.get(id)
+    }
+    /// Rebuilds a finished game that is no longer in memory from storage and keeps it in
+    /// memory again.
+    fn restore_game(&self, export: GameExport) -> Option<Arc<DiscordGame>> {
+        let game = Arc::new(export.to_game()?);
+        debug!(game_id = %export.id, "Restored game from storage");
+        if let Ok(finished_games) = self.finished_games.write().as_deref_mut() {
+            finished_games.insert(export.id, game.clone());
+        }
+        Some(game)
+    }
+    /// Runs `f` on a running game inside its task. Returns `None` if the game is not
+    /// running, in which case `f` is not called.
+    async fn call_game<R, F>(&self, id: &String, f: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut DiscordGame) -> R + Send + 'static,
+    {
+        let handle = self.get_handle(id)?;
+        let result = handle.call(f).await;
+        if result.is_none() && handle.is_closed() && self.get_finished_game(id).is_none() {
+            warn!(game_id = %id, "Game task stopped unexpectedly");
+            if let Ok(games) = self.games.write().as_deref_mut() {
+                games.remove(id);
+            }
+        }
+        result
+    }
+    /// Runs `f` on a running or finished game of `guild_id` without changing it. Finished
+    /// games that are no longer in memory are looked up in the storage of `guild_id`. Games
+    /// of other servers are treated as if they did not exist.
+    async fn with_game<R, F>(&self, guild_id: Option<GuildId>, id: &String, f: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: Fn(&DiscordGame) -> R + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        let in_guild = move |game: &DiscordGame| game.get_guild_id() == guild_id;
+        if let Some(game) = self.get_finished_game(id) {
+            return in_guild(&game).then(|| f(&game));
+        }
+        let call = f.clone();
+        if let Some(result) = self
+            .call_game(id, move |game| in_guild(game).then(|| call(game)))
+            .await
+        {
+            return result;
         }
-        None
+        let game = self.get_finished_game(id).or_else(|| {
+            self.restore_game(
+                self.storage
+                    .load_game(guild_id.map(|guild_id| guild_id.0), id)?,
+            )
+        })?;
+        in_guild(&game).then(|| f(&game))
     }
 }
 
+/// Renders the lobby message of a game with its Join, Leave and Start buttons.
+fn render_lobby(game: &DiscordGame) -> (CreateEmbed, CreateComponents) {
+    let mut embed = CreateEmbed::default();
+    let mut components = CreateComponents::default();
+    game.generate_embed(&mut embed);
+    generate_lobby_buttons(&mut components, game);
+    (embed, components)
+}
+
+/// Renders the message of a started game: its embed and either the choice buttons or, once
+/// the game is done, the History button.
+fn render_game(game: &DiscordGame) -> (CreateEmbed, CreateComponents) {
+    let mut embed = CreateEmbed::default();
+    let mut components = CreateComponents::default();
+    game.generate_embed(&mut embed);
+    match game.is_done() {
+        true => generate_history_button(&mut components, game),
+        false => generate_game_buttons(&mut components, game),
+    };
+    (embed, components)
+}
+
+/// Renders the given page of a game's round history with its page buttons.
+fn render_history(
+    game: &DiscordGame,
+    page: usize,
+    language: Language,
+) -> (CreateEmbed, CreateComponents) {
+    let mut embed = CreateEmbed::default();
+    let mut components = CreateComponents::default();
+    game.generate_history_embed(&mut embed, page, language);
+    generate_page_buttons(
+        &mut components,
+        game,
+        ComponentAction::HistoryPage,
+        page,
+        game.get_history().len(),
+        language,
+    );
+    (embed, components)
+}
+
+/// Renders the given page of a game's full results as seen by `viewer`.
+fn render_standings(
+    game: &DiscordGame,
+    page: usize,
+    viewer: &User,
+    language: Language,
+) -> (CreateEmbed, CreateComponents) {
+    let mut embed = CreateEmbed::default();
+    let mut components = CreateComponents::default();
+    game.generate_standings_embed(&mut embed, page, viewer, language);
+    generate_page_buttons(
+        &mut components,
+        game,
+        ComponentAction::ResultsPage,
+        page,
+        game.get_standings_pages(),
+        language,
+    );
+    (embed, components)
+}
+
 fn generate_game_buttons<'a>(
     components: &'a mut CreateComponents,
-    id: &String,
+    game: &DiscordGame,
 ) -> &'a mut CreateComponents {
+    let language = game.get_language();
+    components.create_action_row(|row| {
+        game.get_rule_set()
+            .choices()
+            .into_iter()
+            .for_each(|choice| {
+                row.create_button(|button| {
+                    button
+                        .label(language.choice(choice))
+                        .emoji(choice_to_emoji(choice))
+                        .style(ButtonStyle::Secondary)
+                        .custom_id(game.custom_id(ComponentAction::Choose(choice)))
+                });
+            });
+        row
+    });
+    generate_history_button(components, game)
+}
+
+fn generate_lobby_buttons<'a>(
+    components: &'a mut CreateComponents,
+    game: &DiscordGame,
+) -> &'a mut CreateComponents {
+    let language = game.get_language();
     components.create_action_row(|row| {
         row.create_button(|button| {
             button
-                .label("Rock")
-                .emoji(ReactionType::Unicode("🪨".to_string()))
-                .style(ButtonStyle::Secondary)
-                .custom_id(&format!("#r:{}", id))
+                .label(language.text("button-join"))
+                .custom_id(game.custom_id(ComponentAction::Join))
         });
         row.create_button(|button| {
             button
-                .label("Paper")
-                .emoji(ReactionType::Unicode("📄".to_string()))
+                .label(language.text("button-leave"))
                 .style(ButtonStyle::Secondary)
-                .custom_id(&format!("#p:{}", id))
+                .custom_id(game.custom_id(ComponentAction::Leave))
         });
         row.create_button(|button| {
             button
-                .label("Scissors")
-                .emoji(ReactionType::Unicode("✂️".to_string()))
+                .label(language.text("button-start"))
+                .custom_id(game.custom_id(ComponentAction::Start))
+        })
+    })
+}
+
+fn generate_history_button<'a>(
+    components: &'a mut CreateComponents,
+    game: &DiscordGame,
+) -> &'a mut CreateComponents {
+    let language = game.get_language();
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .label(language.text("button-history"))
                 .style(ButtonStyle::Secondary)
-                .custom_id(&format!("#s:{}", id))
+                .custom_id(game.custom_id(ComponentAction::ShowHistory))
         });
         row.create_button(|button| {
             button
-                .label("Lizard")
-                .emoji(ReactionType::Unicode("🦎".to_string()))
+                .label(language.text("button-full-results"))
                 .style(ButtonStyle::Secondary)
-                .custom_id(&format!("#l:{}", id))
+                .custom_id(game.custom_id(ComponentAction::ShowResults))
+        })
+    })
+}
+
+/// Creates Previous and Next buttons that send the `action` of the page to show.
+fn generate_page_buttons<'a>(
+    components: &'a mut CreateComponents,
+    game: &DiscordGame,
+    action: fn(usize) -> ComponentAction,
+    page: usize,
+    pages: usize,
+    language: Language,
+) -> &'a mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .label(language.text("button-previous"))
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0)
+                .custom_id(game.custom_id(action(page.saturating_sub(1))))
         });
         row.create_button(|button| {
             button
-                .label("Spock")
-                .emoji(ReactionType::Unicode("🖖".to_string()))
+                .label(language.text("button-next"))
                 .style(ButtonStyle::Secondary)
-                .custom_id(&format!("#S:{}", id))
+                .disabled(page + 1 >= pages)
+                .custom_id(game.custom_id(action(page + 1)))
         })
     })
 }
 
-#[async_trait]
-impl EventHandler for Handler {
-    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+fn generate_rules(language: Language) -> String {
+    [
+        (Choice::Paper, Choice::Rock),
+        (Choice::Rock, Choice::Scissors),
+        (Choice::Scissors, Choice::Lizard),
+        (Choice::Lizard, Choice::Spock),
+        (Choice::Spock, Choice::Rock),
+        (Choice::Rock, Choice::Lizard),
+        (Choice::Lizard, Choice::Paper),
+        (Choice::Paper, Choice::Spock),
+        (Choice::Spock, Choice::Scissors),
+        (Choice::Scissors, Choice::Paper),
+    ]
+    .into_iter()
+    .filter_map(|(winner, loser)| Battle::new((), winner, (), loser).battle())
+    .map(|result| {
+        language.format(
+            "rule",
+            &[
+                ("winner", language.choice(result.winner_choice).into()),
+                ("verb", language.verb(&result.verb).into()),
+                ("loser", language.choice(result.loser_choice).into()),
+            ],
+        ) + "\n"
+    })
+    .collect()
+}
+
+impl Handler {
+    async fn handle_interaction(&self, ctx: Context, interaction: Interaction) {
         match interaction {
             Interaction::ApplicationCommand(command) => {
                 let cmd = command.data.name.as_str();
                 let mut options = command.data.options.clone();
+                let responder = Responder::from(&command);
                 match cmd {
                     "rockpaperscissors" => {
-                        let mut rounds = options
-                            .drain(0..)
-                            .filter_map(|option| {
-                                if option.name == "rounds" {
-                                    return option.value?.as_u64();
+                        let config = self.get_config(command.guild_id);
+                        let (rounds, capped) = config.rounds(
+                            options
+                                .iter()
+                                .find(|option| option.name == "rounds")
+                                .and_then(|option| option.value.as_ref()?.as_u64()),
+                        );
+                        let language = config.language.unwrap_or_else(|| {
+                            Language::from_interaction(
+                                &command.locale,
+                                command.guild_locale.as_ref(),
+                            )
+                        });
+                        let rule_set = match get_string_option(&options, "mode") {
+                            Some(mode) => RuleSet::from_name(&mode),
+                            None => Some(config.default_rule_set),
+                        };
+                        let roles = command
+                            .member
+                            .as_ref()
+                            .map(|member| member.roles.as_slice())
+                            .unwrap_or(&[]);
+                        let mut created = None;
+                        let responded = self
+                            .respond(&ctx.http, responder, false, async {
+                                let refusal = match self.check_open(language).and_then(|_| {
+                                    config.check_access(command.channel_id, roles, language)
+                                }) {
+                                    Err(refusal) => Some(refusal),
+                                    Ok(()) => match rule_set {
+                                        Some(rule_set) if config.allows_mode(rule_set) => None,
+                                        _ => Some(language.text("mode-not-allowed")),
+                                    },
+                                };
+                                if let Some(refusal) = refusal {
+                                    return Reply::ephemeral().content(refusal);
                                 }
-                                None
-                            })
-                            .sum();
-                        if rounds < 1 {
-                            rounds = 1;
-                        }
-                        println!("{}", &rounds);
-                        if let Err(why) = command
-                            .create_interaction_response(&ctx.http, |response| {
-                                response
-                                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                                    .interaction_response_data(|message| {
-                                        match self.new_game(rounds) {
-                                            Some(id) => {
-                                                self.get_game(&id)
-                                                    .unwrap()
-                                                    .lock()
-                                                    .unwrap()
-                                                    .add_player(&command.user);
-                                                message
-                                                    .embed(|embed| {
-                                                        self.get_game(&id)
-                                                            .unwrap()
-                                                            .lock()
-                                                            .unwrap()
-                                                            .generate_embed(embed)
-                                                    })
-                                                    .components(|components| {
-                                                        components.create_action_row(|row| {
-                                                            row.create_button(|button| {
-                                                                button.label("Join").custom_id(
-                                                                    &format!("join:{}", id),
-                                                                )
-                                                            });
-                                                            row.create_button(|button| {
-                                                                button.label("Start").custom_id(
-                                                                    &format!("start:{}", id),
-                                                                )
-                                                            })
-                                                        })
-                                                    });
-                                                return message;
-                                            }
-                                            None => message.content("Ein Fehler ist aufgetreten"),
-                                        }
+                                let rule_set = rule_set.unwrap_or_default();
+                                let id = match self.new_game(
+                                    ctx.http.clone(),
+                                    command.guild_id,
+                                    language,
+                                    rule_set,
+                                    rounds,
+                                    &config,
+                                ) {
+                                    Some(id) => id,
+                                    None => {
+                                        return Reply::message().content(language.text("error"))
+                                    }
+                                };
+                                Span::current().record("game_id", id.as_str());
+                                info!(rounds, rule_set = rule_set.name(), "Game created");
+                                let user = command.user.clone();
+                                let lobby = self
+                                    .call_game(&id, move |game| {
+                                        game.add_player(&user).ok()?;
+                                        Some(render_lobby(game))
                                     })
+                                    .await
+                                    .flatten();
+                                created = Some(id);
+                                match lobby {
+                                    Some((embed, components)) => {
+                                        Reply::message().embed(embed).components(components)
+                                    }
+                                    None => Reply::message().content(language.text("error")),
+                                }
                             })
-                            .await
-                        {
-                            println!("Cannot respond to slash command: {}", why);
+                            .await;
+                        if let (true, Some(id)) = (responded, &created) {
+                            if let Ok(message) = command.get_interaction_response(&ctx.http).await {
+                                self.call_game(id, move |game| {
+                                    game.set_message(message.channel_id, message.id)
+                                })
+                                .await;
+                            }
+                            if capped {
+                                let note = language
+                                    .format("rounds-capped", &[("max", config.max_rounds.into())]);
+                                if let Err(why) = command
+                                    .create_followup_message(&ctx.http, |message| {
+                                        message.ephemeral(true).content(note)
+                                    })
+                                    .await
+                                {
+                                    warn!(error = %why, "Cannot send rounds note");
+                                }
+                            }
                         }
                     }
                     "rules" => {
-                        if let Err(why) = command
-                            .create_interaction_response(&ctx.http, |response| {
-                                response
-                                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                                    .interaction_response_data(|message| {
-                                        message.embed(|embed| {
-                                            embed.description(
-                                                MessageBuilder::new()
-                                                    .push("Paper covers Rock\n")
-                                                    .push("Rock crushes Scissors\n")
-                                                    .push("Scissors decapitates Lizard\n")
-                                                    .push("Lizard poisons Spock\n")
-                                                    .push("Spock vaporizes Rock\n")
-                                                    .push("Rock crushes Lizard\n")
-                                                    .push("Lizard eats Paper\n")
-                                                    .push("Paper disproves Spock\n")
-                                                    .push("Spock smashes Scissors\n")
-                                                    .push("Scissors cuts Paper\n")
-                                                    .build(),
-                                            )
-                                        })
-                                    })
-                            })
-                            .await
-                        {
-                            println!("Cannot respond to component {}", why);
-                        };
+                        let mut embed = CreateEmbed::default();
+                        embed.description(generate_rules(Language::from_interaction(
+                            &command.locale,
+                            command.guild_locale.as_ref(),
+                        )));
+                        self.reply(&ctx.http, responder, Reply::message().embed(embed))
+                            .await;
                     }
-                    _ => {}
-                }
-            }
-            Interaction::MessageComponent(component) => {
-                let user_id = &component.user;
-                let id: String;
-                let cmd: String;
-                {
-                    let cid = component.data.custom_id.clone();
-                    let custom_id = match cid.split_once(':') {
-                        Some(data) => data,
-                        None => return,
-                    };
-                    id = custom_id.1.to_string();
-                    cmd = custom_id.0.to_string();
-                }
-                if let Err(why) = component
-                    .create_interaction_response(&ctx.http, |response| {
-                        let game_arc = self.get_game(&id).unwrap();
-                        let mut game = game_arc.lock().unwrap();
-                        if cmd.eq(&"start".to_string()) {
-                            if game.get_player_count() >= 2 {
-                                game.start_round();
-                                response
-                                    .kind(InteractionResponseType::UpdateMessage)
-                                    .interaction_response_data(|message| {
-                                        message
-                                            .embed(|embed| game.generate_embed(embed))
-                                            .components(|components| {
-                                                generate_game_buttons(components, &id)
-                                            })
-                                    })
-                            } else {
-                                response.kind(InteractionResponseType::UpdateMessage)
+                    "rps" => {
+                        let subcommand = match options.pop() {
+                            Some(subcommand) => subcommand,
+                            None => return,
+                        };
+                        let language = Language::from_locale(&command.locale);
+                        match subcommand.name.as_str() {
+                            "history" => {
+                                let id = normalize_code(
+                                    &get_string_option(&subcommand.options, "game_id")
+                                        .unwrap_or_default(),
+                                );
+                                Span::current().record("game_id", id.as_str());
+                                self.respond(&ctx.http, responder, true, async {
+                                    match self
+                                        .with_game(command.guild_id, &id, move |game| {
+                                            render_history(game, 0, language)
+                                        })
+                                        .await
+                                    {
+                                        Some((embed, components)) => {
+                                            Reply::ephemeral().embed(embed).components(components)
+                                        }
+                                        None => Reply::ephemeral()
+                                            .content(language.text("game-not-found")),
+                                    }
+                                })
+                                .await;
+                            }
+                            "join" => {
+                                let code = normalize_code(
+                                    &get_string_option(&subcommand.options, "code")
+                                        .unwrap_or_default(),
+                                );
+                                Span::current().record("game_id", code.as_str());
+                                self.respond(
+                                    &ctx.http,
+                                    responder,
+                                    true,
+                                    self.join_by_code(ctx.http.clone(), &command, code, language),
+                                )
+                                .await;
                             }
-                        } else if cmd.eq(&"join".to_string()) {
-                            if game.add_player(user_id) {
-                                response
-                                    .kind(InteractionResponseType::UpdateMessage)
-                                    .interaction_response_data(|message| {
-                                        message.embed(|embed| game.generate_embed(embed))
+                            "analyze" => {
+                                let user_id = subcommand
+                                    .options
+                                    .iter()
+                                    .find(|option| option.name == "user")
+                                    .and_then(|option| match option.resolved.as_ref()? {
+                                        CommandDataOptionValue::User(user, _) => Some(user.id),
+                                        _ => None,
                                     })
-                            } else {
-                                response.kind(InteractionResponseType::UpdateMessage)
+                                    .unwrap_or(command.user.id);
+                                self.respond(&ctx.http, responder, false, async {
+                                    let analysis = Analysis::new(&self.storage.load_picks(
+                                        command.guild_id.map(|guild_id| guild_id.0),
+                                        user_id.0,
+                                    ));
+                                    let mut embed = CreateEmbed::default();
+                                    analysis.generate_embed(user_id, language, &mut embed);
+                                    Reply::message().embed(embed)
+                                })
+                                .await;
                             }
-                        } else if cmd.chars().next().unwrap_or('+') == '#' {
-                            game.choose(user_id, cmd.chars().nth(1).unwrap_or(' '));
-                            response
-                                .kind(InteractionResponseType::UpdateMessage)
-                                .interaction_response_data(|message| {
-                                    if game.did_all_choose() {
-                                        message.embed(|embed| game.generate_embed(embed));
-                                        game.battle();
-                                        if game.is_done() {
-                                            message.set_components(CreateComponents(vec![]));
-                                            drop(game);
-                                            self.delete_game(&id);
+                            "export" | "export-all" => {
+                                let format = ExportFormat::from_name(
+                                    &get_string_option(&subcommand.options, "format")
+                                        .unwrap_or_default(),
+                                );
+                                let guild_id = command.guild_id.map(|guild_id| guild_id.0);
+                                let id = get_string_option(&subcommand.options, "game_id");
+                                self.respond(&ctx.http, responder, true, async {
+                                    let export = match id {
+                                        Some(id) => self.export_game(
+                                            guild_id,
+                                            &normalize_code(&id),
+                                            format,
+                                            language,
+                                        ),
+                                        None => self.export_guild(guild_id, format, language),
+                                    };
+                                    match export {
+                                        Ok((filename, data)) => {
+                                            Reply::ephemeral().file(filename, data)
                                         }
-                                        message
-                                    } else {
-                                        message.embed(|embed| game.generate_embed(embed))
+                                        Err(why) => Reply::ephemeral().content(why),
                                     }
                                 })
-                        } else {
-                            response.kind(InteractionResponseType::UpdateMessage)
+                                .await;
+                            }
+                            _ => {}
                         }
+                    }
+                    "rps-config" => {
+                        let language = Language::from_locale(&command.locale);
+                        let guild_id = match command.guild_id {
+                            Some(guild_id) => guild_id,
+                            None => return,
+                        };
+                        let subcommand = match options.pop() {
+                            Some(subcommand) => subcommand,
+                            None => return,
+                        };
+                        let is_admin = command
+                            .member
+                            .as_ref()
+                            .and_then(|member| member.permissions)
+                            .is_some_and(|permissions| permissions.manage_guild());
+                        let key = get_string_option(&subcommand.options, "key");
+                        let mut config = self.get_config(Some(guild_id));
+                        let result = match subcommand.name.as_str() {
+                            _ if !is_admin => Err(language.text("config-admin-only")),
+                            "get" => Ok(None),
+                            "set" => config
+                                .set(
+                                    &key.unwrap_or_default(),
+                                    &get_string_option(&subcommand.options, "value")
+                                        .unwrap_or_default(),
+                                    language,
+                                )
+                                .map(|_| Some(language.text("config-saved"))),
+                            "reset" => {
+                                config.reset(key.as_deref());
+                                Ok(Some(language.text("config-reset-done")))
+                            }
+                            _ => return,
+                        };
+                        self.respond(&ctx.http, responder, true, async {
+                            if let Ok(Some(_)) = result {
+                                self.save_config(guild_id, config.clone());
+                            }
+                            match result {
+                                Ok(content) => {
+                                    let mut embed = CreateEmbed::default();
+                                    generate_config_embed(&config, language, &mut embed);
+                                    let reply = Reply::ephemeral().embed(embed);
+                                    match content {
+                                        Some(content) => reply.content(content),
+                                        None => reply,
+                                    }
+                                }
+                                Err(why) => Reply::ephemeral().content(why),
+                            }
+                        })
+                        .await;
+                    }
+                    _ => {}
+                }
+            }
+            Interaction::MessageComponent(component) => {
+                self.respond(
+                    &ctx.http,
+                    Responder::from(&component),
+                    true,
+                    self.handle_component(ctx.http.clone(), &component),
+                )
+                .await;
+            }
+            _ => {}
+        }
+    }
+    /// Adds the user of `command` to the lobby with the code `code`, which may be in another
+    /// channel, and updates the lobby message.
+    async fn join_by_code(
+        &self,
+        http: Arc<Http>,
+        command: &ApplicationCommandInteraction,
+        code: String,
+        language: Language,
+    ) -> Reply {
+        let config = self.get_config(command.guild_id);
+        let guild_id = command.guild_id;
+        let roles = command
+            .member
+            .as_ref()
+            .map(|member| member.roles.as_slice())
+            .unwrap_or(&[]);
+        // Players may only join lobbies of this server, and only if they could press the
+        // Join button in the lobby's own channel.
+        let lobby = self
+            .call_game(&code, move |game| {
+                (game.get_guild_id() == guild_id).then(|| game.get_message())
+            })
+            .await
+            .flatten();
+        let message = match lobby {
+            Some(message) => message,
+            None => return Reply::ephemeral().content(language.text("game-not-found")),
+        };
+        if let Err(refusal) = self.check_open(language).and_then(|_| {
+            config.check_access(
+                message.map_or(command.channel_id, |(channel_id, _)| channel_id),
+                roles,
+                language,
+            )
+        }) {
+            return Reply::ephemeral().content(refusal);
+        }
+        let user = command.user.clone();
+        let handler = self.clone();
+        let joined = self
+            .call_game(&code, move |game| {
+                game.add_player(&user)?;
+                handler.update_message(&http, game);
+                Ok(game.get_message())
+            })
+            .await;
+        let message = match joined {
+            Some(Ok(joined)) => joined,
+            Some(Err(why)) => return Reply::ephemeral().content(language.game_error(why)),
+            None => return Reply::ephemeral().content(language.text("game-not-found")),
+        };
+        let mut reply = language.format("joined-game", &[("id", code.as_str().into())]);
+        if let Some((channel_id, message_id)) = message {
+            reply.push('\n');
+            reply.push_str(&message_id.link(channel_id, guild_id));
+        }
+        Reply::ephemeral().content(reply)
+    }
+    /// Works out the answer to a button click. Buttons of a game either change the game and
+    /// update its message, or open the history or the full results.
+    async fn handle_component(
+        &self,
+        http: Arc<Http>,
+        component: &MessageComponentInteraction,
+    ) -> Reply {
+        let user_id = &component.user;
+        let language = Language::from_locale(&component.locale);
+        let component_id = match ComponentId::decode(&component.data.custom_id) {
+            Ok(component_id) => component_id,
+            Err(why) => {
+                debug!(error = %why, "Rejected component");
+                return Reply::ephemeral().content(language.text("component-outdated"));
+            }
+        };
+        let id = component_id.game_id.clone();
+        Span::current().record("game_id", id.as_str());
+        if component_id.action == ComponentAction::Join {
+            let roles = component
+                .member
+                .as_ref()
+                .map(|member| member.roles.as_slice())
+                .unwrap_or(&[]);
+            if let Err(refusal) = self.check_open(language).and_then(|_| {
+                self.get_config(component.guild_id).check_access(
+                    component.channel_id,
+                    roles,
+                    language,
+                )
+            }) {
+                return Reply::ephemeral().content(refusal);
+            }
+        }
+        let (page, show_standings) = match component_id.action {
+            ComponentAction::ShowHistory => (Some(0), false),
+            ComponentAction::HistoryPage(page) => (Some(page), false),
+            ComponentAction::ShowResults => (Some(0), true),
+            ComponentAction::ResultsPage(page) => (Some(page), true),
+            _ => (None, false),
+        };
+        if let Some(page) = page {
+            let viewer = user_id.clone();
+            let opens = matches!(
+                component_id.action,
+                ComponentAction::ShowHistory | ComponentAction::ShowResults
+            );
+            let view = self
+                .with_game(component.guild_id, &id, move |game| {
+                    if !game.accepts(&component_id) {
+                        return None;
+                    }
+                    Some(match show_standings {
+                        true => render_standings(game, page, &viewer, language),
+                        false => render_history(game, page, language),
                     })
-                    .await
-                {
-                    println!("Cannot respond to component {}", why);
+                })
+                .await;
+            return match view {
+                Some(Some((embed, components))) => match opens {
+                    true => Reply::ephemeral(),
+                    false => Reply::update(),
+                }
+                .embed(embed)
+                .components(components),
+                Some(None) => Reply::ephemeral().content(language.text("component-invalid")),
+                None => Reply::ephemeral().content(language.text("game-not-found")),
+            };
+        }
+        let handler = self.clone();
+        let user = user_id.clone();
+        let (channel_id, message_id) = (component.channel_id, component.message.id);
+        let update = self
+            .call_game(&id, move |game| {
+                if !game.accepts(&component_id) {
+                    return None;
+                }
+                game.set_message(channel_id, message_id);
+                let result = match component_id.action {
+                    ComponentAction::Start => game.start().map(|_| {
+                        info!(players = game.get_player_count(), "Game started");
+                    }),
+                    ComponentAction::Join => {
+                        game.add_player(&user).map(|_| debug!("Player joined"))
+                    }
+                    ComponentAction::Leave => {
+                        game.remove_player(&user).map(|_| debug!("Player left"))
+                    }
+                    ComponentAction::Choose(choice) => {
+                        game.choose(&user, choice)
+                            .and_then(|_| match game.did_all_choose() {
+                                true => handler.resolve_round(game),
+                                false => Ok(()),
+                            })
+                    }
+                    _ => Ok(()),
                 };
+                // Many players may click at once, so the message is edited through the
+                // outbox, which only sends the newest state of the game.
+                Some(result.map(|_| handler.update_message(&http, game)))
+            })
+            .await;
+        match update {
+            Some(Some(Ok(()))) => Reply::acknowledge(),
+            Some(Some(Err(error))) => Reply::ephemeral().content(language.game_error(error)),
+            Some(None) => Reply::ephemeral().content(language.text("component-invalid")),
+            None => Reply::ephemeral().content(language.text("game-not-found")),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let span = info_span!(
+            "interaction",
+            kind = field::Empty,
+            command = field::Empty,
+            guild_id = field::Empty,
+            user_id = field::Empty,
+            game_id = field::Empty,
+        );
+        let kind = match &interaction {
+            Interaction::ApplicationCommand(command) => {
+                span.record("command", command.data.name.as_str());
+                span.record("guild_id", command.guild_id.map(|guild_id| guild_id.0));
+                span.record("user_id", command.user.id.0);
+                "command"
             }
-            _ => {}
+            Interaction::MessageComponent(component) => {
+                span.record("command", component.data.custom_id.as_str());
+                span.record("guild_id", component.guild_id.map(|guild_id| guild_id.0));
+                span.record("user_id", component.user.id.0);
+                "component"
+            }
+            _ => "other",
+        };
+        span.record("kind", kind);
+        self.metrics.interactions.with_label_values(&[kind]).inc();
+        let timer = self
+            .metrics
+            .interaction_latency
+            .with_label_values(&[kind])
+            .start_timer();
+        async {
+            debug!("Handling interaction");
+            self.handle_interaction(ctx, interaction).await
+        }
+        .instrument(span)
+        .await;
+        timer.observe_duration();
+    }
+
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        debug!(shard = %event.shard_id, stage = %event.new, "Shard stage changed");
+        if let Ok(shards) = self.shards.write().as_deref_mut() {
+            shards.insert(event.shard_id, event.new == ConnectionStage::Connected);
         }
     }
 
     async fn ready(&self, ctx: Context, ready: Ready) {
-        println!("{} is connected!", ready.user.name);
-        let _commands = Command::create_global_application_command(&ctx.http, |command| {
-            command
-                .name("rockpaperscissors")
-                .add_option(
-                    serenity::builder::CreateApplicationCommandOption(HashMap::from([]))
-                        .name("rounds")
-                        .description("how many rounds are played")
-                        .kind(serenity::model::prelude::command::CommandOptionType::Integer)
-                        .min_int_value(1)
-                        .max_int_value(15)
-                        .clone(),
-                )
-                .description("Start a new Game")
-        })
-        .await;
-        let _commands = Command::create_global_application_command(&ctx.http, |command| {
-            command
-                .name("rules")
-                .description("Get the rules of the game.")
-        })
-        .await;
+        info!(user = %ready.user.name, shard = ctx.shard_id, "Connected");
+        if let Ok(shards) = self.shards.write().as_deref_mut() {
+            // Shards that have not reported yet count as disconnected.
+            let total = ready.shard.map_or(1, |[_, total]| total);
+            (0..total).for_each(|shard| {
+                shards.entry(ShardId(shard)).or_insert(false);
+            });
+            shards.insert(ShardId(ctx.shard_id), true);
+        }
+        let scope = self.command_scope;
+        match sync_commands(&ctx.http, scope).await {
+            Ok(true) => info!(%scope, "Registered commands"),
+            Ok(false) => info!(%scope, "Commands are up to date"),
+            Err(why) => error!(%scope, error = %why, "Cannot register commands"),
+        }
+    }
+}
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = match signal(SignalKind::terminate()) {
+            Ok(terminate) => terminate,
+            Err(why) => {
+                error!(error = %why, "Cannot listen for SIGTERM");
+                let _ = tokio::signal::ctrl_c().await;
+                return;
+            }
+        };
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
     }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
 }
+
 #[tokio::main]
 async fn main() {
-    dotenv().unwrap();
-    let token = std::env::var("DISCORD_TOKEN").unwrap();
-    let mut client = Client::builder(
-        token,
-        GatewayIntents::GUILD_MESSAGE_REACTIONS.union(GatewayIntents::GUILD_MESSAGES),
-    )
-    .event_handler(Handler::new())
-    .await
-    .unwrap();
-    if let Err(why) = client.start().await {
-        println!("Client error: {:?}", why);
-    }
-    println!("Hello, world!");
+    dotenv().ok();
+    let mut args = std::env::args().skip(1);
+    let mut config_path = None;
+    let mut check_config = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check-config" => check_config = true,
+            "--config" => match args.next() {
+                Some(path) => config_path = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--config needs a path");
+                    std::process::exit(2);
+                }
+            },
+            _ => {
+                eprintln!("Usage: discordbot [--config PATH] [--check-config]");
+                std::process::exit(2);
+            }
+        }
+    }
+    let config = match Config::load(config_path) {
+        Ok(config) => config,
+        Err(errors) => {
+            eprintln!("Invalid configuration:");
+            errors.iter().for_each(|error| eprintln!("  - {}", error));
+            std::process::exit(1);
+        }
+    };
+    if check_config {
+        println!("Configuration is valid:\n{}", config);
+        return;
+    }
+    let level = config.log_level.parse().unwrap_or(LevelFilter::INFO);
+    match config.log_format.as_str() {
+        "json" => tracing_subscriber::fmt()
+            .json()
+            .with_max_level(level)
+            .init(),
+        _ => tracing_subscriber::fmt().with_max_level(level).init(),
+    }
+    let handler = Handler::new(&config);
+    let shutdown_handler = handler.clone();
+    let mut client = Client::builder(&config.token, config.intents)
+        .event_handler(handler.clone())
+        .await
+        .unwrap();
+    if let Some(address) = config.http_address {
+        let http = client.cache_and_http.http.clone();
+        tokio::spawn(async move {
+            info!(%address, "Starting HTTP server");
+            if let Err(why) = server::serve(address, handler, http).await {
+                error!(%address, error = %why, "HTTP server failed");
+            }
+        });
+    }
+    let shard_manager = client.shard_manager.clone();
+    let http = client.cache_and_http.http.clone();
+    let (stopped, shut_down) = oneshot::channel();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        info!("Shutting down");
+        shutdown_handler.shutdown(&http).await;
+        shard_manager.lock().await.shutdown_all().await;
+        let _ = stopped.send(());
+    });
+    // The client does not always return once its shards are shut down, for example while
+    // it is still waiting to reconnect, so stop waiting for it after the shutdown.
+    let result = tokio::select! {
+        result = async {
+            match config.shard_count {
+                Some(shard_count) => client.start_shards(shard_count).await,
+                None => client.start().await,
+            }
+        } => result,
+        _ = shut_down => Ok(()),
+    };
+    if let Err(why) = result {
+        error!(error = %why, "Client error");
+    }
+    info!("Stopped");
 }
@@ -0,0 +1,97 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+    TextEncoder,
+};
+use tracing::error;
+
+/// Counters and gauges exposed on the `/metrics` endpoint in the Prometheus text format.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub active_games: IntGauge,
+    pub games_created: IntCounter,
+    pub games_finished: IntCounter,
+    pub games_expired: IntCounter,
+    pub interactions: IntCounterVec,
+    pub interaction_errors: IntCounterVec,
+    pub interaction_latency: HistogramVec,
+    pub message_edits: IntCounterVec,
+    pub rounds: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new_custom(Some("rps".to_string()), None).unwrap();
+        let metrics = Metrics {
+            active_games: IntGauge::new("active_games", "Games that have not finished yet")
+                .unwrap(),
+            games_created: IntCounter::new("games_created_total", "Games created").unwrap(),
+            games_finished: IntCounter::new(
+                "games_finished_total",
+                "Games that reached their last round",
+            )
+            .unwrap(),
+            games_expired: IntCounter::new(
+                "games_expired_total",
+                "Games that ended because a round timed out",
+            )
+            .unwrap(),
+            interactions: IntCounterVec::new(
+                Opts::new("interactions_total", "Interactions handled by type"),
+                &["type"],
+            )
+            .unwrap(),
+            interaction_errors: IntCounterVec::new(
+                Opts::new(
+                    "interaction_errors_total",
+                    "Interaction responses Discord rejected, by type",
+                ),
+                &["type"],
+            )
+            .unwrap(),
+            interaction_latency: HistogramVec::new(
+                HistogramOpts::new(
+                    "interaction_duration_seconds",
+                    "Time taken to handle an interaction, by type",
+                ),
+                &["type"],
+            )
+            .unwrap(),
+            message_edits: IntCounterVec::new(
+                Opts::new(
+                    "message_edits_total",
+                    "Game message edits by result: sent, coalesced, retried or failed",
+                ),
+                &["result"],
+            )
+            .unwrap(),
+            rounds: IntCounterVec::new(
+                Opts::new("rounds_total", "Rounds played by rule set"),
+                &["rule_set"],
+            )
+            .unwrap(),
+            registry,
+        };
+        [
+            Box::new(metrics.active_games.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(metrics.games_created.clone()),
+            Box::new(metrics.games_finished.clone()),
+            Box::new(metrics.games_expired.clone()),
+            Box::new(metrics.interactions.clone()),
+            Box::new(metrics.interaction_errors.clone()),
+            Box::new(metrics.interaction_latency.clone()),
+            Box::new(metrics.message_edits.clone()),
+            Box::new(metrics.rounds.clone()),
+        ]
+        .into_iter()
+        .for_each(|collector| metrics.registry.register(collector).unwrap());
+        metrics
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buffer = vec![];
+        if let Err(why) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            error!(error = %why, "Cannot encode metrics");
+        }
+        buffer
+    }
+}
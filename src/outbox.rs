@@ -0,0 +1,283 @@
+use crate::Handler;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::http::{Http, HttpError, StatusCode};
+use serenity::model::id::{ChannelId, MessageId};
+use serenity::Error as SerenityError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{info_span, warn, Instrument};
+
+/// How often a failed edit is retried before it counts as a permanent failure.
+const MAX_RETRIES: u32 = 5;
+/// The wait before the first retry. Every further retry waits twice as long.
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The new contents of a game message.
+pub struct MessageEdit {
+    pub content: Option<String>,
+    pub embed: CreateEmbed,
+    pub components: CreateComponents,
+}
+
+/// Edits of game messages that are waiting to be sent.
+///
+/// Each message has at most one edit in flight. Edits queued meanwhile replace each other,
+/// so after a burst of clicks only the newest state of the game is sent. Discord's rate-limit
+/// buckets are tracked by serenity's ratelimiter, which holds a request back until its bucket
+/// resets; edits that are refused anyway are retried with backoff.
+#[derive(Clone, Default)]
+pub struct Outbox {
+    /// A message is in here while an edit of it is being sent, together with the edit that
+    /// should follow.
+    pending: Arc<Mutex<HashMap<MessageId, Option<MessageEdit>>>>,
+}
+
+impl Outbox {
+    /// Queues `edit`. Returns whether no edit of the message is being sent yet, in which case
+    /// the caller has to start sending, and whether an edit that was still waiting got
+    /// replaced.
+    fn push(&self, message_id: MessageId, edit: MessageEdit) -> (bool, bool) {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(_) => return (false, false),
+        };
+        let starts = !pending.contains_key(&message_id);
+        let replaced = pending.insert(message_id, Some(edit)).flatten().is_some();
+        (starts, replaced)
+    }
+    /// Takes the newest queued edit of a message, or stops tracking the message if there is
+    /// none.
+    fn take(&self, message_id: MessageId) -> Option<MessageEdit> {
+        let mut pending = match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(_) => return None,
+        };
+        let edit = pending.get_mut(&message_id).and_then(Option::take);
+        if edit.is_none() {
+            pending.remove(&message_id);
+        }
+        edit
+    }
+    /// Drops the queued edits of a message that cannot be edited anymore.
+    fn forget(&self, message_id: MessageId) {
+        if let Ok(pending) = self.pending.lock().as_deref_mut() {
+            pending.remove(&message_id);
+        }
+    }
+    /// Whether every queued edit has been sent.
+    pub fn is_idle(&self) -> bool {
+        self.pending
+            .lock()
+            .map(|pending| pending.is_empty())
+            .unwrap_or(true)
+    }
+}
+
+/// How long to wait before retrying after `why`, or `None` if retrying cannot help.
+fn retry_delay(why: &SerenityError, attempt: u32) -> Option<Duration> {
+    let backoff = BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    match why {
+        SerenityError::Http(error) => match error.as_ref() {
+            HttpError::UnsuccessfulRequest(response)
+                if response.status_code == StatusCode::TOO_MANY_REQUESTS
+                    || response.status_code.is_server_error() =>
+            {
+                Some(backoff)
+            }
+            HttpError::Request(_) => Some(backoff),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+async fn send_edit(
+    http: &Http,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    edit: &MessageEdit,
+    handler: &Handler,
+) -> Result<(), SerenityError> {
+    let mut attempt = 0;
+    loop {
+        let why = match channel_id
+            .edit_message(http, message_id, |message| {
+                if let Some(content) = &edit.content {
+                    message.content(content);
+                }
+                message
+                    .set_embed(edit.embed.clone())
+                    .set_components(edit.components.clone())
+            })
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(why) => why,
+        };
+        let delay = match retry_delay(&why, attempt) {
+            Some(delay) if attempt < MAX_RETRIES => delay,
+            _ => return Err(why),
+        };
+        attempt += 1;
+        handler.count_edit("retried");
+        warn!(error = %why, attempt, "Retrying message edit");
+        sleep(delay).await;
+    }
+}
+
+impl Handler {
+    /// Queues an edit of the message of a game. If the message cannot be edited even after
+    /// retrying, the game is told through [`Handler::message_failed`].
+    ///
+    /// A queued edit replaces the one waiting before it, so edits of a running game have to
+    /// be queued from inside its task, see [`Handler::update_message`].
+    pub fn queue_edit(
+        &self,
+        http: Arc<Http>,
+        game_id: &str,
+        (channel_id, message_id): (ChannelId, MessageId),
+        edit: MessageEdit,
+    ) {
+        let (starts, replaced) = self.outbox.push(message_id, edit);
+        if replaced {
+            self.count_edit("coalesced");
+        }
+        if !starts {
+            return;
+        }
+        let handler = self.clone();
+        let game_id = game_id.to_string();
+        let span = info_span!("outbox", game_id = %game_id, message_id = message_id.0);
+        tokio::spawn(
+            async move {
+                while let Some(edit) = handler.outbox.take(message_id) {
+                    if let Err(why) =
+                        send_edit(&http, channel_id, message_id, &edit, &handler).await
+                    {
+                        handler.count_edit("failed");
+                        handler.outbox.forget(message_id);
+                        handler
+                            .message_failed(&http, &game_id, (channel_id, message_id), why)
+                            .await;
+                        return;
+                    }
+                    handler.count_edit("sent");
+                }
+            }
+            .instrument(span),
+        );
+    }
+    /// Waits until every queued edit has been sent or has failed, at most for `limit`.
+    pub async fn flush_edits(&self, limit: Duration) {
+        let step = Duration::from_millis(50);
+        let mut waited = Duration::ZERO;
+        while !self.outbox.is_idle() && waited < limit {
+            sleep(step).await;
+            waited += step;
+        }
+    }
+    fn count_edit(&self, result: &str) {
+        self.metrics
+            .message_edits
+            .with_label_values(&[result])
+            .inc();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::http::error::ErrorResponse;
+
+    fn edit(content: &str) -> MessageEdit {
+        MessageEdit {
+            content: Some(content.to_string()),
+            embed: CreateEmbed::default(),
+            components: CreateComponents::default(),
+        }
+    }
+
+    fn status_error(status_code: StatusCode) -> SerenityError {
+        SerenityError::Http(Box::new(HttpError::UnsuccessfulRequest(ErrorResponse {
+            status_code,
+            url: "https://discord.com/api/v10/channels/1/messages/2"
+                .parse()
+                .unwrap(),
+            error: serde_json::from_value(serde_json::json!({"code": 0, "message": ""})).unwrap(),
+        })))
+    }
+
+    #[test]
+    fn first_push_starts_sending() {
+        let outbox = Outbox::default();
+        assert_eq!(outbox.push(MessageId(1), edit("a")), (true, false));
+        assert_eq!(outbox.push(MessageId(2), edit("b")), (true, false));
+        assert!(!outbox.is_idle());
+    }
+
+    #[test]
+    fn waiting_edit_is_replaced_by_newer_one() {
+        let outbox = Outbox::default();
+        outbox.push(MessageId(1), edit("a"));
+        assert_eq!(
+            outbox.take(MessageId(1)).and_then(|edit| edit.content),
+            Some("a".to_string())
+        );
+        // "a" is being sent, so "b" waits and is then replaced by "c".
+        assert_eq!(outbox.push(MessageId(1), edit("b")), (false, false));
+        assert_eq!(outbox.push(MessageId(1), edit("c")), (false, true));
+        assert_eq!(
+            outbox.take(MessageId(1)).and_then(|edit| edit.content),
+            Some("c".to_string())
+        );
+    }
+
+    #[test]
+    fn drained_message_is_removed() {
+        let outbox = Outbox::default();
+        outbox.push(MessageId(1), edit("a"));
+        assert!(outbox.take(MessageId(1)).is_some());
+        assert!(!outbox.is_idle());
+        assert!(outbox.take(MessageId(1)).is_none());
+        assert!(outbox.is_idle());
+        // The next edit has to start sending again.
+        assert_eq!(outbox.push(MessageId(1), edit("b")), (true, false));
+        outbox.forget(MessageId(1));
+        assert!(outbox.is_idle());
+    }
+
+    #[test]
+    fn transient_errors_are_retried_with_backoff() {
+        for status in [
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::BAD_GATEWAY,
+        ] {
+            let why = status_error(status);
+            assert_eq!(retry_delay(&why, 0), Some(BASE_BACKOFF));
+            assert_eq!(retry_delay(&why, 2), Some(BASE_BACKOFF * 4));
+            assert_eq!(retry_delay(&why, 20), Some(MAX_BACKOFF));
+        }
+    }
+
+    #[test]
+    fn permanent_errors_are_not_retried() {
+        for status in [
+            StatusCode::BAD_REQUEST,
+            StatusCode::FORBIDDEN,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert_eq!(retry_delay(&status_error(status), 0), None);
+        }
+        assert_eq!(
+            retry_delay(&SerenityError::Http(Box::new(HttpError::InvalidWebhook)), 0),
+            None
+        );
+        assert_eq!(retry_delay(&SerenityError::Other("other"), 0), None);
+    }
+}
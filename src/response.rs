@@ -0,0 +1,247 @@
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::http::Http;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::message_component::MessageComponentInteraction;
+use serenity::model::application::interaction::{InteractionResponseType, MessageFlags};
+use serenity::model::channel::AttachmentType;
+use serenity::Result;
+use std::borrow::Cow;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::debug;
+
+/// How long an interaction may take to answer before it is deferred. Discord drops
+/// interactions that are not answered within three seconds.
+const DEFER_AFTER: Duration = Duration::from_millis(1500);
+
+/// The answer to an interaction, independent of whether it is sent right away or after the
+/// interaction was deferred.
+#[derive(Default)]
+pub struct Reply {
+    acknowledge: bool,
+    update: bool,
+    ephemeral: bool,
+    content: Option<String>,
+    embed: Option<CreateEmbed>,
+    components: Option<CreateComponents>,
+    file: Option<(String, Vec<u8>)>,
+}
+
+impl Reply {
+    /// A new message everyone in the channel sees.
+    pub fn message() -> Self {
+        Reply::default()
+    }
+    /// A new message only the user of the interaction sees.
+    pub fn ephemeral() -> Self {
+        Reply {
+            ephemeral: true,
+            ..Reply::default()
+        }
+    }
+    /// Replaces the message whose button was pressed.
+    pub fn update() -> Self {
+        Reply {
+            update: true,
+            ..Reply::default()
+        }
+    }
+    /// Confirms a button click without changing its message. The message is edited
+    /// separately, through the outbox.
+    pub fn acknowledge() -> Self {
+        Reply {
+            acknowledge: true,
+            ..Reply::default()
+        }
+    }
+    pub fn content(mut self, content: String) -> Self {
+        self.content = Some(content);
+        self
+    }
+    pub fn embed(mut self, embed: CreateEmbed) -> Self {
+        self.embed = Some(embed);
+        self
+    }
+    pub fn components(mut self, components: CreateComponents) -> Self {
+        self.components = Some(components);
+        self
+    }
+    pub fn file(mut self, filename: String, data: Vec<u8>) -> Self {
+        self.file = Some((filename, data));
+        self
+    }
+    pub fn is_ephemeral(&self) -> bool {
+        self.ephemeral
+    }
+}
+
+/// Calls the same method on either kind of interaction. Their builders share the same
+/// methods but not a trait, so the closure is spelled out once for each.
+macro_rules! on_interaction {
+    ($responder:expr, $interaction:ident => $call:expr) => {
+        match $responder {
+            Responder::Command($interaction) => $call,
+            Responder::Component($interaction) => $call,
+        }
+    };
+}
+
+/// An interaction that is answered with a [`Reply`].
+#[derive(Clone, Copy)]
+pub enum Responder<'a> {
+    Command(&'a ApplicationCommandInteraction),
+    Component(&'a MessageComponentInteraction),
+}
+
+impl<'a> From<&'a ApplicationCommandInteraction> for Responder<'a> {
+    fn from(command: &'a ApplicationCommandInteraction) -> Self {
+        Responder::Command(command)
+    }
+}
+
+impl<'a> From<&'a MessageComponentInteraction> for Responder<'a> {
+    fn from(component: &'a MessageComponentInteraction) -> Self {
+        Responder::Component(component)
+    }
+}
+
+impl Responder<'_> {
+    /// The label of the interaction in logs and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Responder::Command(_) => "command",
+            Responder::Component(_) => "component",
+        }
+    }
+    /// Answers the interaction with the reply `work` produces. If the work is not done
+    /// within [`DEFER_AFTER`], the interaction is deferred and the reply is sent once it is
+    /// ready.
+    ///
+    /// A deferred command shows a placeholder message, which is only visible to the user if
+    /// `ephemeral` is set. A reply that does not fit the placeholder is sent as a follow-up
+    /// and the placeholder is deleted. Deferred components show no placeholder, so
+    /// `ephemeral` does not matter for them.
+    pub async fn respond(
+        &self,
+        http: &Http,
+        ephemeral: bool,
+        work: impl Future<Output = Reply>,
+    ) -> Result<()> {
+        tokio::pin!(work);
+        if let Ok(reply) = timeout(DEFER_AFTER, &mut work).await {
+            return self.create(http, reply).await;
+        }
+        debug!(kind = self.kind(), "Deferring interaction");
+        // The work has to finish even if Discord refuses the deferral, as it may have
+        // already changed a game.
+        let deferred = self.defer(http, ephemeral).await;
+        let reply = work.await;
+        deferred?;
+        if reply.acknowledge {
+            return Ok(());
+        }
+        let edits_original = reply.file.is_none()
+            && match self {
+                Responder::Command(_) => reply.ephemeral == ephemeral,
+                Responder::Component(_) => reply.update,
+            };
+        if edits_original {
+            return self.edit_original(http, reply).await;
+        }
+        self.follow_up(http, reply).await?;
+        match self {
+            Responder::Command(command) => command.delete_original_interaction_response(http).await,
+            Responder::Component(_) => Ok(()),
+        }
+    }
+    async fn create(&self, http: &Http, reply: Reply) -> Result<()> {
+        if reply.acknowledge {
+            return on_interaction!(self, interaction => interaction.defer(http).await);
+        }
+        let kind = match (self, reply.update) {
+            (Responder::Component(_), true) => InteractionResponseType::UpdateMessage,
+            _ => InteractionResponseType::ChannelMessageWithSource,
+        };
+        on_interaction!(self, interaction => interaction
+            .create_interaction_response(http, |response| {
+                response.kind(kind).interaction_response_data(|message| {
+                    if reply.ephemeral {
+                        message.flags(MessageFlags::EPHEMERAL);
+                    }
+                    if let Some(content) = reply.content {
+                        message.content(content);
+                    }
+                    if let Some(embed) = reply.embed {
+                        message.add_embed(embed);
+                    }
+                    if let Some(components) = reply.components {
+                        message.set_components(components);
+                    }
+                    if let Some((filename, data)) = reply.file {
+                        message.add_file(AttachmentType::Bytes {
+                            data: Cow::from(data),
+                            filename,
+                        });
+                    }
+                    message
+                })
+            })
+            .await)
+    }
+    async fn defer(&self, http: &Http, ephemeral: bool) -> Result<()> {
+        match self {
+            Responder::Command(command) => {
+                command
+                    .create_interaction_response(http, |response| {
+                        response
+                            .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                            .interaction_response_data(|message| message.ephemeral(ephemeral))
+                    })
+                    .await
+            }
+            Responder::Component(component) => component.defer(http).await,
+        }
+    }
+    async fn edit_original(&self, http: &Http, reply: Reply) -> Result<()> {
+        on_interaction!(self, interaction => interaction
+            .edit_original_interaction_response(http, |message| {
+                if let Some(content) = reply.content {
+                    message.content(content);
+                }
+                if let Some(embed) = reply.embed {
+                    message.set_embed(embed);
+                }
+                if let Some(components) = reply.components {
+                    message.set_components(components);
+                }
+                message
+            })
+            .await
+            .map(|_| ()))
+    }
+    async fn follow_up(&self, http: &Http, reply: Reply) -> Result<()> {
+        on_interaction!(self, interaction => interaction
+            .create_followup_message(http, |message| {
+                message.ephemeral(reply.ephemeral);
+                if let Some(content) = reply.content {
+                    message.content(content);
+                }
+                if let Some(embed) = reply.embed {
+                    message.add_embed(embed);
+                }
+                if let Some(components) = reply.components {
+                    message.set_components(components);
+                }
+                if let Some((filename, data)) = reply.file {
+                    message.add_file(AttachmentType::Bytes {
+                        data: Cow::from(data),
+                        filename,
+                    });
+                }
+                message
+            })
+            .await
+            .map(|_| ()))
+    }
+}
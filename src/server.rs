@@ -0,0 +1,202 @@
+use crate::discordgame::DiscordGame;
+use crate::export::GameExport;
+use crate::Handler;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use prometheus::{Encoder, TextEncoder};
+use rps_core::ruleset::RuleSet;
+use rps_core::state::GameState;
+use serde::Serialize;
+use serenity::http::Http;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+
+#[derive(Serialize)]
+struct Health {
+    gateway: bool,
+    storage: bool,
+}
+
+#[derive(Serialize)]
+struct GameSummary {
+    id: String,
+    guild_id: Option<u64>,
+    rule_set: RuleSet,
+    state: GameState,
+    round: u64,
+    rounds: u64,
+    players: usize,
+    chosen: usize,
+    deadline: Option<u64>,
+}
+
+impl From<&DiscordGame> for GameSummary {
+    fn from(game: &DiscordGame) -> Self {
+        GameSummary {
+            id: game.get_id().clone(),
+            guild_id: game.get_guild_id().map(|guild_id| guild_id.0),
+            rule_set: game.get_rule_set(),
+            state: game.get_state(),
+            round: game.get_round(),
+            rounds: game.get_rounds(),
+            players: game.get_player_count(),
+            chosen: game.get_finished_players(),
+            deadline: game
+                .get_deadline()
+                .and_then(|deadline| Some(deadline.duration_since(UNIX_EPOCH).ok()?.as_secs())),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct GameDetail {
+    #[serde(flatten)]
+    summary: GameSummary,
+    game: GameExport,
+}
+
+/// Runs the embedded HTTP server on `address` until the process exits.
+pub async fn serve(address: SocketAddr, handler: Handler, http: Arc<Http>) -> hyper::Result<()> {
+    let make_service = make_service_fn(move |_| {
+        let handler = handler.clone();
+        let http = http.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let handler = handler.clone();
+                let http = http.clone();
+                async move { Ok::<_, Infallible>(route(&handler, &http, request).await) }
+            }))
+        }
+    });
+    Server::try_bind(&address)?.serve(make_service).await
+}
+
+async fn route(handler: &Handler, http: &Arc<Http>, request: Request<Body>) -> Response<Body> {
+    let path: Vec<&str> = request
+        .uri()
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    match (request.method(), path.as_slice()) {
+        (&Method::GET, ["metrics"]) => metrics(handler),
+        (&Method::GET, ["healthz"]) => health(handler),
+        (_, ["admin", ..]) if !is_authorized(handler, &request) => status(StatusCode::UNAUTHORIZED),
+        (&Method::GET, ["admin", "games"]) => list_games(handler).await,
+        (&Method::GET, ["admin", "games", id]) => inspect_game(handler, id).await,
+        (&Method::POST, ["admin", "games", id, "end"]) => {
+            match handler
+                .end_game_early(http, &id.to_string(), "game-force-ended")
+                .await
+            {
+                true => status(StatusCode::NO_CONTENT),
+                false => status(StatusCode::NOT_FOUND),
+            }
+        }
+        _ => status(StatusCode::NOT_FOUND),
+    }
+}
+
+fn status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::from(status.canonical_reason().unwrap_or_default()));
+    *response.status_mut() = status;
+    response
+}
+
+fn json<T: Serialize>(status: StatusCode, value: &T) -> Response<Body> {
+    let mut response = match serde_json::to_vec(value) {
+        Ok(data) => Response::new(Body::from(data)),
+        Err(_) => return self::status(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    *response.status_mut() = status;
+    if let Ok(content_type) = "application/json".parse() {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    response
+}
+
+/// Compares the bearer token with the configured admin token without returning early,
+/// so the response time does not reveal how much of the token was right.
+fn is_authorized(handler: &Handler, request: &Request<Body>) -> bool {
+    let expected = match &handler.admin_token {
+        Some(token) => token.as_bytes(),
+        None => return false,
+    };
+    let given = match request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => token.as_bytes(),
+        None => return false,
+    };
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn metrics(handler: &Handler) -> Response<Body> {
+    let active_games = handler.games.read().map(|games| games.len()).unwrap_or(0);
+    handler.metrics.active_games.set(active_games as i64);
+    let mut response = Response::new(Body::from(handler.metrics.encode()));
+    if let Ok(content_type) = TextEncoder::new().format_type().parse() {
+        response.headers_mut().insert(CONTENT_TYPE, content_type);
+    }
+    response
+}
+
+fn health(handler: &Handler) -> Response<Body> {
+    let health = Health {
+        gateway: handler.is_connected(),
+        storage: handler.storage.check().is_ok(),
+    };
+    match health.gateway && health.storage {
+        true => json(StatusCode::OK, &health),
+        false => json(StatusCode::SERVICE_UNAVAILABLE, &health),
+    }
+}
+
+async fn list_games(handler: &Handler) -> Response<Body> {
+    let ids: Vec<String> = match handler.games.read() {
+        Ok(games) => games.keys().cloned().collect(),
+        Err(_) => return status(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+    let mut games = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(summary) = handler
+            .call_game(&id, |game| GameSummary::from(&*game))
+            .await
+        {
+            games.push(summary);
+        }
+    }
+    json(StatusCode::OK, &games)
+}
+
+async fn inspect_game(handler: &Handler, id: &str) -> Response<Body> {
+    let id = id.to_string();
+    let detail = |game: &DiscordGame| GameDetail {
+        summary: GameSummary::from(game),
+        game: GameExport::from(game),
+    };
+    // Games of every server can be inspected, so a game that is no longer in memory is
+    // searched for in all of them.
+    let detail = match handler.call_game(&id, move |game| detail(game)).await {
+        Some(detail) => Some(detail),
+        None => handler
+            .get_finished_game(&id)
+            .or_else(|| handler.restore_game(handler.storage.find_game(&id)?))
+            .map(|game| detail(&game)),
+    };
+    match detail {
+        Some(detail) => json(StatusCode::OK, &detail),
+        None => status(StatusCode::NOT_FOUND),
+    }
+}
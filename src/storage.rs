@@ -0,0 +1,106 @@
+use crate::analytics::Pick;
+use crate::export::GameExport;
+use crate::guildconfig::GuildConfig;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Clone)]
+pub struct Storage {
+    path: PathBuf,
+}
+
+impl Storage {
+    pub fn new(path: PathBuf) -> Self {
+        Storage { path }
+    }
+    fn guild_path(&self, guild_id: Option<u64>) -> PathBuf {
+        self.scoped_path("games", guild_id)
+    }
+    fn scoped_path(&self, kind: &str, guild_id: Option<u64>) -> PathBuf {
+        match guild_id {
+            Some(guild_id) => self.path.join(kind).join(guild_id.to_string()),
+            None => self.path.join(kind).join("direct"),
+        }
+    }
+    /// Checks that the storage directory exists and is writable.
+    pub fn check(&self) -> io::Result<()> {
+        fs::create_dir_all(&self.path)?;
+        let probe = self.path.join(".probe");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(probe)
+    }
+    pub fn save_game(&self, game: &GameExport) -> io::Result<()> {
+        let path = self.guild_path(game.guild_id);
+        fs::create_dir_all(&path)?;
+        fs::write(
+            path.join(format!("{}.json", game.id)),
+            serde_json::to_vec(game)?,
+        )
+    }
+    pub fn load_game(&self, guild_id: Option<u64>, id: &str) -> Option<GameExport> {
+        if !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        let data = fs::read(self.guild_path(guild_id).join(format!("{}.json", id))).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+    /// Looks for a game in the storage of every server.
+    pub fn find_game(&self, id: &str) -> Option<GameExport> {
+        if !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+        fs::read_dir(self.path.join("games"))
+            .ok()?
+            .filter_map(|entry| fs::read(entry.ok()?.path().join(format!("{}.json", id))).ok())
+            .find_map(|data| serde_json::from_slice(&data).ok())
+    }
+    pub fn load_games(&self, guild_id: Option<u64>) -> Vec<GameExport> {
+        let entries = match fs::read_dir(self.guild_path(guild_id)) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+        let mut games: Vec<GameExport> = entries
+            .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+            .filter_map(|data| serde_json::from_slice(&data).ok())
+            .collect();
+        games.sort_by(|a, b| a.id.cmp(&b.id));
+        games
+    }
+    pub fn append_pick(&self, guild_id: Option<u64>, user_id: u64, pick: &Pick) -> io::Result<()> {
+        let path = self.scoped_path("picks", guild_id);
+        fs::create_dir_all(&path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.join(format!("{}.jsonl", user_id)))?;
+        serde_json::to_writer(&mut file, pick)?;
+        file.write_all(b"\n")
+    }
+    pub fn load_picks(&self, guild_id: Option<u64>, user_id: u64) -> Vec<Pick> {
+        fs::read_to_string(
+            self.scoped_path("picks", guild_id)
+                .join(format!("{}.jsonl", user_id)),
+        )
+        .map(|data| {
+            data.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+    pub fn save_guild_config(&self, guild_id: u64, config: &GuildConfig) -> io::Result<()> {
+        let path = self.path.join("guilds");
+        fs::create_dir_all(&path)?;
+        fs::write(
+            path.join(format!("{}.json", guild_id)),
+            serde_json::to_vec_pretty(config)?,
+        )
+    }
+    pub fn load_guild_config(&self, guild_id: u64) -> Option<GuildConfig> {
+        let data = fs::read(self.path.join("guilds").join(format!("{}.json", guild_id))).ok()?;
+        let mut config: GuildConfig = serde_json::from_slice(&data).ok()?;
+        config.repair();
+        Some(config)
+    }
+}